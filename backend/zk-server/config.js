@@ -11,12 +11,18 @@ module.exports = {
 
   // Backend keypair (Stellar secret key)
   BACKEND_SECRET: process.env.BACKEND_SECRET,
+  // key_id the contract registered the backend key under (0 = initial key)
+  BACKEND_KEY_ID: Number(process.env.BACKEND_KEY_ID || 0),
+  // Seconds an attestation stays valid for on-chain submission
+  ATTESTATION_TTL: Number(process.env.ATTESTATION_TTL || 600),
 
   // Contract
   CONTRACT_ID: process.env.CONTRACT_ID,
   
   // Stellar network
   STELLAR_NETWORK: process.env.STELLAR_NETWORK || "testnet",
+  NETWORK_PASSPHRASE:
+    process.env.NETWORK_PASSPHRASE || "Test SDF Network ; September 2015",
   STELLAR_RPC_URL:
     process.env.STELLAR_RPC_URL || "https://soroban-testnet.stellar.org:443",
 
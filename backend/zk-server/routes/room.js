@@ -5,7 +5,8 @@ const roomService = require("../services/roomService");
 const crypto = require("crypto");
 const { generateProof } = require("../services/proofService");
 const verifyProof = require("../services/verifyService");
-const { signAttestation } = require("../services/attestationService");
+const { getContractRoundId } = require("../services/contractService");
+const { signAttestation, signFailure } = require("../services/attestationService");
 const { getNextNonce } = require("../services/nonceService");
 const { validateTrialSolution } = require("../config/trials");
 
@@ -146,9 +147,28 @@ router.post("/:roomId/submit-proof", async (req, res) => {
     
     if (!isCorrectAnswer) {
       console.log("❌ WRONG ANSWER! Rejecting submission.\n");
+
+      // Sign the failure so it can be recorded on-chain (record_failure)
+      const nonce = getNextNonce(playerWallet);
+      const gameRound = await getContractRoundId();
+      const { keyId, signature, expiresAt } = signFailure({
+        trialId: roundId,
+        player: playerWallet,
+        nonce,
+        gameRound,
+      });
+
       return res.status(400).json({
         success: false,
         error: `Incorrect solution for round ${roundId}`,
+        failureAttestation: {
+          signatures: [{ keyId, signature }],
+          nonce,
+          roundId,
+          gameRound,
+          expiresAt,
+          player: playerWallet,
+        },
       });
     }
     
@@ -188,16 +208,26 @@ router.post("/:roomId/submit-proof", async (req, res) => {
     // STEP 6: Get nonce and sign attestation (for on-chain submission)
     const nonce = getNextNonce(playerWallet);
     // Use actual trial roundId for unique proof per trial
-    const signature = signAttestation(roundId, playerWallet, solutionHash, nonce);
+    // Attestations are bound to the contract's current game round
+    const gameRound = await getContractRoundId();
+    const { keyId, signature, expiresAt } = signAttestation({
+      trialId: roundId,
+      player: playerWallet,
+      solutionHash,
+      nonce,
+      gameRound,
+    });
 
     res.json({
       success: true,
       ...result,
       attestation: {
-        signature,
+        signatures: [{ keyId, signature }],
         solutionHash,
         nonce,
         roundId: roundId,  // Trial number (1, 2, 3...)
+        gameRound,
+        expiresAt,
         player: playerWallet,
       },
     });
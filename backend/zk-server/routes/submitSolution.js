@@ -6,7 +6,8 @@ const express = require("express");
 const crypto = require("crypto");
 const { generateProof } = require("../services/proofService");
 const verifyProof = require("../services/verifyService");
-const { signAttestation } = require("../services/attestationService");
+const { getContractRoundId } = require("../services/contractService");
+const { signAttestation, signFailure } = require("../services/attestationService");
 const { getNextNonce } = require("../services/nonceService");
 const { validateTrialSolution } = require("../config/trials");
 
@@ -31,6 +32,9 @@ const router = express.Router();
  *     "nonce": 123
  *   }
  * }
+ *
+ * A wrong answer returns 400 with a signed "failureAttestation" for the
+ * contract's record_failure.
  */
 router.post("/", async (req, res) => {
   try {
@@ -52,9 +56,28 @@ router.post("/", async (req, res) => {
     
     if (!isCorrectAnswer) {
       console.log("❌ WRONG ANSWER! Rejecting submission.\n");
+
+      // Sign the failure so it can be recorded on-chain (record_failure)
+      const nonce = getNextNonce(player);
+      const gameRound = await getContractRoundId();
+      const { keyId, signature, expiresAt } = signFailure({
+        trialId: roundId,
+        player,
+        nonce,
+        gameRound,
+      });
+
       return res.status(400).json({
         success: false,
         error: "Incorrect solution for this trial",
+        failureAttestation: {
+          signatures: [{ keyId, signature }],
+          nonce,
+          roundId,
+          gameRound,
+          expiresAt,
+          player,
+        },
       });
     }
     
@@ -86,14 +109,24 @@ router.post("/", async (req, res) => {
     // STEP 6: Sign attestation (backend approves CORRECT solution)
     console.log("✍️  Signing attestation...");
     // Use actual trial roundId for unique proof per trial
-    const signature = signAttestation(roundId, player, solutionHash, nonce);
+    // Attestations are bound to the contract's current game round
+    const gameRound = await getContractRoundId();
+    const { keyId, signature, expiresAt } = signAttestation({
+      trialId: roundId,
+      player,
+      solutionHash,
+      nonce,
+      gameRound,
+    });
 
     // STEP 7: Return attestation
     const attestation = {
-      signature,
+      signatures: [{ keyId, signature }],
       solutionHash,
       nonce,
       roundId,  // Trial number (1, 2, 3...)
+      gameRound,
+      expiresAt,
       player,
     };
 
@@ -0,0 +1,158 @@
+// ============================================================================
+// ATTESTATION MESSAGE BUILDER
+// ============================================================================
+//
+// JavaScript twin of the throne-attestation crate (contracts/throne-attestation).
+// The contract rebuilds this exact message from the submit_proof arguments,
+// so every signer MUST build it here rather than by hand.
+//
+// Layout (version 2):
+//   domain tag     "ZK-THRONE/ATTEST" (16 bytes)
+//   version        1 byte
+//   network ID     32 bytes (SHA256 of the network passphrase)
+//   contract       u32 BE length + strkey bytes
+//   game round     u32 BE
+//   trial ID       u32 BE
+//   player         u32 BE length + strkey bytes
+//   solution hash  32 bytes
+//   nonce          u64 BE
+//   expires at     u64 BE (ledger timestamp)
+//
+// Failed trials (record_failure) use the same layout with the
+// "ZK-THRONE/FAILED" domain tag and no solution hash.
+//
+// Attestors sign SHA256(message) with Ed25519.
+
+const crypto = require("crypto");
+
+const DOMAIN_TAG = Buffer.from("ZK-THRONE/ATTEST", "utf8");
+const FAILURE_DOMAIN_TAG = Buffer.from("ZK-THRONE/FAILED", "utf8");
+const MESSAGE_VERSION = 2;
+
+/**
+ * Network ID the contract reads from the ledger
+ * @param {string} passphrase - Stellar network passphrase
+ * @returns {Buffer} SHA256 of the passphrase
+ */
+function networkId(passphrase) {
+  return crypto.createHash("sha256").update(passphrase, "utf8").digest();
+}
+
+function u32(value) {
+  const buffer = Buffer.alloc(4);
+  buffer.writeUInt32BE(value);
+  return buffer;
+}
+
+function u64(value) {
+  const buffer = Buffer.alloc(8);
+  buffer.writeBigUInt64BE(BigInt(value));
+  return buffer;
+}
+
+function prefixed(strkey) {
+  const bytes = Buffer.from(strkey, "utf8");
+  return Buffer.concat([u32(bytes.length), bytes]);
+}
+
+function hashBytes(solutionHash) {
+  const hex = solutionHash.startsWith("0x") ? solutionHash.slice(2) : solutionHash;
+  const bytes = Buffer.from(hex, "hex");
+  if (bytes.length !== 32) {
+    throw new Error("Solution hash must be 32 bytes");
+  }
+  return bytes;
+}
+
+/**
+ * Encode a trial completion attestation
+ * @param {object} attestation
+ * @param {string} attestation.networkPassphrase - Network the contract runs on
+ * @param {string} attestation.contractId - Throne contract strkey (C...)
+ * @param {number} attestation.gameRound - Contract game round (get_round_id)
+ * @param {number} attestation.trialId - Trial number (1, 2, 3...)
+ * @param {string} attestation.player - Player strkey (G...)
+ * @param {string} attestation.solutionHash - 32-byte hex, with or without 0x
+ * @param {number|bigint} attestation.nonce - Player nonce
+ * @param {number|bigint} attestation.expiresAt - Ledger timestamp the attestation expires
+ * @returns {Buffer} Encoded message
+ */
+function encodeTrialAttestation({
+  networkPassphrase,
+  contractId,
+  gameRound,
+  trialId,
+  player,
+  solutionHash,
+  nonce,
+  expiresAt,
+}) {
+  return Buffer.concat([
+    DOMAIN_TAG,
+    Buffer.from([MESSAGE_VERSION]),
+    networkId(networkPassphrase),
+    prefixed(contractId),
+    u32(gameRound),
+    u32(trialId),
+    prefixed(player),
+    hashBytes(solutionHash),
+    u64(nonce),
+    u64(expiresAt),
+  ]);
+}
+
+/**
+ * Digest attestors sign: SHA256 of the encoded message
+ * @param {object} attestation - See encodeTrialAttestation
+ * @returns {Buffer} 32-byte digest
+ */
+function trialAttestationDigest(attestation) {
+  return crypto.createHash("sha256").update(encodeTrialAttestation(attestation)).digest();
+}
+
+/**
+ * Encode a failed trial attestation
+ * @param {object} failure - See encodeTrialAttestation, without solutionHash
+ * @returns {Buffer} Encoded message
+ */
+function encodeFailureAttestation({
+  networkPassphrase,
+  contractId,
+  gameRound,
+  trialId,
+  player,
+  nonce,
+  expiresAt,
+}) {
+  return Buffer.concat([
+    FAILURE_DOMAIN_TAG,
+    Buffer.from([MESSAGE_VERSION]),
+    networkId(networkPassphrase),
+    prefixed(contractId),
+    u32(gameRound),
+    u32(trialId),
+    prefixed(player),
+    u64(nonce),
+    u64(expiresAt),
+  ]);
+}
+
+/**
+ * Digest attestors sign for a failure: SHA256 of the encoded message
+ * @param {object} failure - See encodeFailureAttestation
+ * @returns {Buffer} 32-byte digest
+ */
+function failureAttestationDigest(failure) {
+  return crypto.createHash("sha256").update(encodeFailureAttestation(failure)).digest();
+}
+
+module.exports = {
+  DOMAIN_TAG,
+  FAILURE_DOMAIN_TAG,
+  MESSAGE_VERSION,
+  networkId,
+  encodeTrialAttestation,
+  trialAttestationDigest,
+  encodeFailureAttestation,
+  failureAttestationDigest,
+};
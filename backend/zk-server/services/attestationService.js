@@ -3,7 +3,9 @@
 // ============================================================================
 
 const { Keypair } = require("@stellar/stellar-sdk");
-const crypto = require("crypto");
+const config = require("../config");
+const { trialAttestationDigest, failureAttestationDigest } = require("./attestationMessage");
+const { CONTRACT_ID } = require("./contractService");
 
 if (!process.env.BACKEND_SECRET) {
   throw new Error("BACKEND_SECRET not set in environment variables");
@@ -14,39 +16,60 @@ const backendKeypair = Keypair.fromSecret(process.env.BACKEND_SECRET);
 console.log("🔑 Backend Public Key:", backendKeypair.publicKey());
 
 /**
- * Sign attestation after proof verification
- * Message format MUST match the contract's verify_signature function:
- * SHA256(roundId_u32_be + player_string + solutionHash_bytes + nonce_u64_be)
+ * Sign a trial completion attestation after proof verification
+ * The message is the version 2 layout built by attestationMessage.js, which
+ * the contract's submit_proof rebuilds byte-for-byte.
+ * @returns {{keyId: number, signature: string, expiresAt: number}}
+ *   Signature (base64) under the backend's contract key_id
  */
-function signAttestation(roundId, player, solutionHash, nonce) {
-  // Create the message to sign (matching Soroban contract format)
-  const buffer = Buffer.alloc(4 + player.length + 32 + 8);
-  let offset = 0;
+function signAttestation({ trialId, player, solutionHash, nonce, gameRound }) {
+  const expiresAt = Math.floor(Date.now() / 1000) + config.ATTESTATION_TTL;
+  const digest = trialAttestationDigest({
+    networkPassphrase: config.NETWORK_PASSPHRASE,
+    contractId: CONTRACT_ID,
+    gameRound,
+    trialId,
+    player,
+    solutionHash,
+    nonce,
+    expiresAt,
+  });
 
-  // Add roundId (u32, big-endian, 4 bytes)
-  buffer.writeUInt32BE(roundId, offset);
-  offset += 4;
-
-  // Add player address (string bytes)
-  Buffer.from(player, 'utf8').copy(buffer, offset);
-  offset += player.length;
-
-  // Add solution hash (32 bytes)
-  // Remove "0x" if present and convert hex to bytes
-  const hashHex = solutionHash.startsWith('0x') ? solutionHash.slice(2) : solutionHash;
-  Buffer.from(hashHex, 'hex').copy(buffer, offset);
-  offset += 32;
+  // Sign with Ed25519
+  const signature = backendKeypair.sign(digest);
 
-  // Add nonce (u64, big-endian, 8 bytes)
-  buffer.writeBigUInt64BE(BigInt(nonce), offset);
+  return {
+    keyId: config.BACKEND_KEY_ID,
+    signature: signature.toString("base64"),
+    expiresAt,
+  };
+}
 
-  // Hash the entire message with SHA-256
-  const message = crypto.createHash("sha256").update(buffer).digest();
+/**
+ * Sign a failed trial attestation after a wrong answer
+ * The contract's record_failure rebuilds the message to lock the player out.
+ * @returns {{keyId: number, signature: string, expiresAt: number}}
+ *   Signature (base64) under the backend's contract key_id
+ */
+function signFailure({ trialId, player, nonce, gameRound }) {
+  const expiresAt = Math.floor(Date.now() / 1000) + config.ATTESTATION_TTL;
+  const digest = failureAttestationDigest({
+    networkPassphrase: config.NETWORK_PASSPHRASE,
+    contractId: CONTRACT_ID,
+    gameRound,
+    trialId,
+    player,
+    nonce,
+    expiresAt,
+  });
 
-  // Sign with Ed25519
-  const signature = backendKeypair.sign(message);
+  const signature = backendKeypair.sign(digest);
 
-  return signature.toString("base64");
+  return {
+    keyId: config.BACKEND_KEY_ID,
+    signature: signature.toString("base64"),
+    expiresAt,
+  };
 }
 
-module.exports = { signAttestation, backendKeypair };
\ No newline at end of file
+module.exports = { signAttestation, signFailure, backendKeypair };
@@ -51,6 +51,40 @@ async function getContractNonce(playerAddress) {
   }
 }
 
+/**
+ * Get the contract's current game round
+ * @returns {Promise<number>} Game round (1 if the query fails)
+ */
+async function getContractRoundId() {
+  try {
+    const operation = contract.call("get_round_id");
+
+    // Simulate to get the result (read-only, no transaction)
+    const simulateResponse = await server.simulateTransaction(
+      operation
+    );
+
+    if (simulateResponse.error) {
+      console.error("❌ Contract query error:", simulateResponse.error);
+      return 1;
+    }
+
+    const resultValue = simulateResponse.result?.retval;
+    if (!resultValue) {
+      return 1;
+    }
+
+    const roundId = Number(resultValue.u32?.() ?? 1);
+    console.log(`📊 Contract game round: ${roundId}`);
+    return roundId;
+  } catch (error) {
+    console.error("❌ Failed to get contract game round:", error.message);
+    return 1;
+  }
+}
+
 module.exports = {
+  CONTRACT_ID,
   getContractNonce,
+  getContractRoundId,
 };
@@ -0,0 +1,179 @@
+#![no_std]
+
+//! # Throne Attestation Messages
+//!
+//! Versioned, domain-separated encoding of the message backend attestors
+//! sign for a trial completion or a failed trial. The Throne contract and
+//! Rust signers both use these builders, so they agree byte-for-byte on
+//! what is signed.
+//!
+//! Attestors sign `SHA256(message)` with Ed25519.
+//!
+//! ## Layout (version 2)
+//!
+//! | Field           | Encoding                          |
+//! |-----------------|-----------------------------------|
+//! | domain tag      | `DOMAIN_TAG` (16 bytes)           |
+//! | version         | `MESSAGE_VERSION` (1 byte)        |
+//! | network ID      | 32 bytes (SHA256 of passphrase)   |
+//! | contract        | u32 BE length + strkey bytes      |
+//! | game round      | u32 BE                            |
+//! | trial ID        | u32 BE                            |
+//! | player          | u32 BE length + strkey bytes      |
+//! | solution hash   | 32 bytes                          |
+//! | nonce           | u64 BE                            |
+//! | expires at      | u64 BE (ledger timestamp)         |
+//!
+//! Binding the network, contract and game round means an attestation for a
+//! testnet deployment, another contract or a previous round is useless
+//! anywhere else.
+//!
+//! ## Failure layout (version 2)
+//!
+//! Same as above with `FAILURE_DOMAIN_TAG` as the domain tag and without
+//! the solution hash, so a failure can never pass for a completion.
+//!
+//! ## Signing from Rust
+//!
+//! ```ignore
+//! let mut message = Vec::new();
+//! attestation.encode(|chunk| message.extend_from_slice(chunk));
+//! let signature = signing_key.sign(&Sha256::digest(&message));
+//! ```
+
+/// Prefix separating Throne attestations from any other signed data
+pub const DOMAIN_TAG: &[u8; 16] = b"ZK-THRONE/ATTEST";
+
+/// Prefix of failed trial attestations
+pub const FAILURE_DOMAIN_TAG: &[u8; 16] = b"ZK-THRONE/FAILED";
+
+/// Current message version (version 1 was the unversioned
+/// `roundId || player || solutionHash || nonce` layout)
+pub const MESSAGE_VERSION: u8 = 2;
+
+/// Backend attestation that `player` solved `trial_id`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TrialAttestation<'a> {
+    pub network_id: [u8; 32],
+    pub contract: &'a [u8], // Throne contract strkey
+    pub game_round: u32,
+    pub trial_id: u32,
+    pub player: &'a [u8], // Player strkey
+    pub solution_hash: [u8; 32],
+    pub nonce: u64,
+    pub expires_at: u64,
+}
+
+impl TrialAttestation<'_> {
+    /// Feed the encoded message to `write`, chunk by chunk
+    pub fn encode(&self, mut write: impl FnMut(&[u8])) {
+        write(DOMAIN_TAG);
+        write(&[MESSAGE_VERSION]);
+        write(&self.network_id);
+        write_prefixed(&mut write, self.contract);
+        write(&self.game_round.to_be_bytes());
+        write(&self.trial_id.to_be_bytes());
+        write_prefixed(&mut write, self.player);
+        write(&self.solution_hash);
+        write(&self.nonce.to_be_bytes());
+        write(&self.expires_at.to_be_bytes());
+    }
+
+    /// Length of the encoded message in bytes
+    pub fn encoded_len(&self) -> usize {
+        let mut len = 0;
+        self.encode(|chunk| len += chunk.len());
+        len
+    }
+}
+
+/// Backend attestation that `player` failed `trial_id`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FailureAttestation<'a> {
+    pub network_id: [u8; 32],
+    pub contract: &'a [u8], // Throne contract strkey
+    pub game_round: u32,
+    pub trial_id: u32,
+    pub player: &'a [u8], // Player strkey
+    pub nonce: u64,
+    pub expires_at: u64,
+}
+
+impl FailureAttestation<'_> {
+    /// Feed the encoded message to `write`, chunk by chunk
+    pub fn encode(&self, mut write: impl FnMut(&[u8])) {
+        write(FAILURE_DOMAIN_TAG);
+        write(&[MESSAGE_VERSION]);
+        write(&self.network_id);
+        write_prefixed(&mut write, self.contract);
+        write(&self.game_round.to_be_bytes());
+        write(&self.trial_id.to_be_bytes());
+        write_prefixed(&mut write, self.player);
+        write(&self.nonce.to_be_bytes());
+        write(&self.expires_at.to_be_bytes());
+    }
+}
+
+/// Write variable-length bytes with a u32 big-endian length prefix
+fn write_prefixed(write: &mut impl FnMut(&[u8]), bytes: &[u8]) {
+    write(&(bytes.len() as u32).to_be_bytes());
+    write(bytes);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encoding_layout() {
+        let attestation = TrialAttestation {
+            network_id: [1u8; 32],
+            contract: b"CONTRACT",
+            game_round: 2,
+            trial_id: 3,
+            player: b"PLAYER",
+            solution_hash: [4u8; 32],
+            nonce: 5,
+            expires_at: 6,
+        };
+
+        let mut message = [0u8; 256];
+        let mut len = 0;
+        attestation.encode(|chunk| {
+            message[len..len + chunk.len()].copy_from_slice(chunk);
+            len += chunk.len();
+        });
+
+        assert_eq!(len, attestation.encoded_len());
+        assert_eq!(len, 16 + 1 + 32 + (4 + 8) + 4 + 4 + (4 + 6) + 32 + 8 + 8);
+        assert_eq!(&message[..16], DOMAIN_TAG);
+        assert_eq!(message[16], MESSAGE_VERSION);
+        assert_eq!(&message[49..53], &8u32.to_be_bytes());
+        assert_eq!(&message[53..61], b"CONTRACT");
+        assert_eq!(&message[len - 8..len], &6u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_failure_encoding_layout() {
+        let failure = FailureAttestation {
+            network_id: [1u8; 32],
+            contract: b"CONTRACT",
+            game_round: 2,
+            trial_id: 3,
+            player: b"PLAYER",
+            nonce: 5,
+            expires_at: 6,
+        };
+
+        let mut message = [0u8; 256];
+        let mut len = 0;
+        failure.encode(|chunk| {
+            message[len..len + chunk.len()].copy_from_slice(chunk);
+            len += chunk.len();
+        });
+
+        assert_eq!(len, 16 + 1 + 32 + (4 + 8) + 4 + 4 + (4 + 6) + 8 + 8);
+        assert_eq!(&message[..16], FAILURE_DOMAIN_TAG);
+        assert_eq!(&message[len - 16..len - 8], &5u64.to_be_bytes());
+    }
+}
@@ -4,15 +4,15 @@
 //!
 //! This Soroban contract:
 //! - Verifies RISC Zero zkVM proofs for trial completion
-//! - Tracks player progress across trials
-//! - Assigns "King" status when all trials are completed
+//! - Tracks player progress and weighted score across trials
+//! - Assigns "King" status when a player reaches the required score
 //!
 //! ## Architecture
 //!
 //! 1. Player completes trial → generates ZK proof off-chain
 //! 2. Frontend submits proof → contract verifies
-//! 3. Contract increments completed_trials counter
-//! 4. When all trials done → player becomes King
+//! 3. Contract adds the trial's weight to the player's score
+//! 4. When the required score is reached → player becomes King
 //!
 //! ## Security Model
 //!
@@ -20,11 +20,33 @@
 //! - Journal data contains trial_id, player, round_id
 //! - Each trial can only be completed once per round
 //! - King assignment is deterministic and immutable per round
+//! - Invite-only rounds only accept proofs from registered players
+//! - In team rounds the first team to reach the required score is crowned
+//!
+//! ## Batch Settlement
+//!
+//! For large rounds an operator can post a Merkle root of completions
+//! instead of one transaction per trial. Each leaf commits to the digests of
+//! the proof's receipt and journal. After the dispute window anyone can
+//! claim leaves from the root. During the window anyone can invalidate the
+//! root by revealing a leaf whose receipt and journal fail verification, or
+//! dispute a leaf they can't reveal: the operator then has to publish its
+//! receipt and journal on-chain, or the root is invalidated.
+//!
+//! ## Oracle
+//!
+//! Partner contracts query King and trial completion status through the
+//! versioned `ThroneOracle` interface from the `throne-interface` crate.
 
+// Contract interfaces spell out `soroban_sdk::Vec`: the contract macros only
+// recognise vectors by the `Vec` name, not by the alias
 use soroban_sdk::{
     contract, contractimpl, contracterror, contracttype, log, symbol_short, Address, Bytes,
-    BytesN, Env, Map, Symbol, Vec as SorobanVec,
+    BytesN, Env, Vec as SorobanVec,
 };
+use throne_interface::{ReignInfo, ThroneOracle, ORACLE_VERSION};
+
+mod merkle;
 
 // ============================================================================
 // Errors
@@ -43,6 +65,28 @@ pub enum Error {
     NotEnoughTrialsCompleted = 7,
     UnauthorizedAdmin = 8,
     InvalidImageId = 9,
+    NotRegistered = 10,
+    AlreadyRegistered = 11,
+    RegistrationClosed = 12,
+    InvalidMerkleProof = 13,
+    TeamModeDisabled = 14,
+    AlreadyInTeam = 15,
+    TeamFull = 16,
+    UnauthorizedOperator = 17,
+    RootAlreadyPosted = 18,
+    RootNotPosted = 19,
+    RootChallenged = 20,
+    DisputeWindowOpen = 21,
+    DisputeWindowClosed = 22,
+    ChallengeRejected = 23,
+    NotTeamCaptain = 24,
+    TeamExists = 25,
+    NotInvited = 26,
+    NotInTeam = 27,
+    DisputeOpen = 28,
+    DisputeNotFound = 29,
+    ResponseWindowOpen = 30,
+    TeamDisbanded = 31,
 }
 
 // ============================================================================
@@ -60,7 +104,81 @@ pub enum DataKey {
     CompletedTrials(u32, Address),                // (round_id, player) -> Set of trial IDs
     RoundLocked(u32),                             // Is round locked from new submissions
     ImageId,                                       // RISC Zero image ID
-    RequiredTrials,                                // Number of trials needed
+    RequiredScore,                                 // Score needed to become King
+    TrialWeight(BytesN<32>),                       // trial_id -> score weight
+    RoundConfig(u32),                              // round_id -> RoundConfig
+    Registered(u32, Address),                      // (round_id, player) -> registered
+    RegisteredCount(u32),                          // round_id -> number of registered players
+    RegisteredPlayer(u32, u32),                    // (round_id, index) -> player
+    PlayerTeam(u32, Address),                      // (round_id, player) -> team_id
+    TeamMembers(u32, u32),                         // (round_id, team_id) -> Vec<Address>
+    TeamProgress(u32, u32),                        // (round_id, team_id) -> TeamProgress
+    TeamCompletedTrials(u32, u32),                 // (round_id, team_id) -> Set of trial IDs
+    TeamKing(u32),                                 // Winning team for round
+    TeamCaptain(u32, u32),                         // (round_id, team_id) -> captain
+    TeamInvite(u32, u32, Address),                 // (round_id, team_id, player) -> invited
+    TeamDisbanded(u32, u32),                       // (round_id, team_id) -> disbanded
+    Reign(u32),                                    // round_id -> ReignInfo
+    Operator,                                      // Batch settlement operator
+    DisputeWindow,                                 // Seconds a posted root can be challenged
+    CompletionRoot(u32),                           // round_id -> CompletionRoot
+    LeafDispute(u32, BytesN<32>),                  // (round_id, leaf hash) -> LeafDispute
+}
+
+/// How players get into a round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Registration {
+    /// Anyone can submit proofs
+    Open,
+    /// Only players registered by the admin
+    Allowlist,
+    /// Players registering with an inclusion proof against this Merkle root
+    MerkleRoot(BytesN<32>),
+    /// Players registering themselves before this ledger timestamp
+    SelfRegistration(u64),
+}
+
+/// How trial completions count towards a team
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TeamMode {
+    /// Individual play only
+    Disabled,
+    /// Each trial scores once per team, whoever completes it first
+    UniquePerTrial,
+    /// Every member's completion adds to the team score
+    SumPerMember,
+}
+
+/// Per-round configuration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundConfig {
+    pub registration: Registration,
+    pub team_mode: TeamMode,
+}
+
+/// Team progress in a round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TeamProgress {
+    pub team_id: u32,
+    pub round_id: u32,
+    pub trials_completed: u32,
+    pub score: u32,
+    pub last_trial_timestamp: u64,
+    pub is_king: bool,
+}
+
+/// Winning team of a round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TeamKing {
+    pub team_id: u32,
+    pub round_id: u32,
+    pub members: soroban_sdk::Vec<Address>,
+    pub crowned_at: u64,
 }
 
 /// Player progress in a round
@@ -70,143 +188,1090 @@ pub struct PlayerProgress {
     pub player: Address,
     pub round_id: u32,
     pub trials_completed: u32,
+    pub score: u32,
     pub last_trial_timestamp: u64,
     pub is_king: bool,
 }
 
-/// Trial completion record
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct TrialCompletion {
-    pub trial_id: BytesN<32>,
-    pub player: Address,
-    pub round_id: u32,
-    pub timestamp: u64,
-    pub solution_hash: BytesN<32>,
-}
+/// A trial completion committed to by a batch settlement root
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompletionLeaf {
+    pub player: Address,
+    pub trial_id: BytesN<32>,
+    pub journal_digest: BytesN<32>, // SHA256 of the proof journal
+    pub receipt_digest: BytesN<32>, // SHA256 of the proof receipt
+}
+
+/// Merkle root of trial completions posted by the operator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompletionRoot {
+    pub root: BytesN<32>,
+    pub posted_at: u64,
+    pub dispute_deadline: u64,
+    pub challenged: bool,
+    pub open_disputes: u32,
+}
+
+/// A leaf whose receipt and journal the operator has been asked to publish
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeafDispute {
+    pub root: BytesN<32>,
+    pub challenger: Address,
+    pub respond_by: u64,
+}
+
+/// Trial completion record
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TrialCompletion {
+    pub trial_id: BytesN<32>,
+    pub player: Address,
+    pub round_id: u32,
+    pub timestamp: u64,
+    pub solution_hash: BytesN<32>,
+}
+
+// ============================================================================
+// Storage TTL Configuration
+// ============================================================================
+
+const LEDGER_TTL_THRESHOLD: u32 = 518_400; // ~30 days
+const LEDGER_TTL_EXTENSION: u32 = 518_400;
+
+// ============================================================================
+// Scoring Defaults
+// ============================================================================
+
+const DEFAULT_REQUIRED_SCORE: u32 = 7;
+const DEFAULT_TRIAL_WEIGHT: u32 = 1; // Trials without a registered weight
+
+const MAX_PAGE_SIZE: u32 = 100; // Max entries returned by paginated queries
+const MAX_TEAM_SIZE: u32 = 16;
+
+const DEFAULT_DISPUTE_WINDOW: u64 = 86_400; // 1 day
+
+// ============================================================================
+// Contract
+// ============================================================================
+
+#[contract]
+pub struct ThroneContract;
+
+#[contractimpl]
+impl ThroneContract {
+    // ========================================================================
+    // Initialization
+    // ========================================================================
+
+    /// Initialize the throne contract
+    ///
+    /// # Arguments
+    /// * `admin` - Admin address for contract management
+    /// * `image_id` - RISC Zero image ID for proof verification
+    /// * `required_score` - Score needed to become King (default: 7)
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        image_id: BytesN<32>,
+        required_score: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+
+        // Check not already initialized
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        // Store admin and config
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::ImageId, &image_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::RequiredScore, &required_score);
+        env.storage().instance().set(&DataKey::CurrentRoundId, &1u32);
+
+        log!(
+            &env,
+            "Throne Contract Initialized: admin={}, required_score={}",
+            admin,
+            required_score
+        );
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Core Functions
+    // ========================================================================
+
+    /// Submit a ZK proof of trial completion
+    ///
+    /// # Arguments
+    /// * `player` - Player address (must match proof journal)
+    /// * `receipt` - RISC Zero receipt bytes
+    /// * `journal` - Journal data (trial_id, player, round_id, solution_hash)
+    /// * `round_id` - Round identifier
+    ///
+    /// # Returns
+    /// * `PlayerProgress` - Updated player progress
+    pub fn submit_proof(
+        env: Env,
+        player: Address,
+        receipt: Bytes,
+        journal: Bytes,
+        round_id: u32,
+    ) -> Result<PlayerProgress, Error> {
+        player.require_auth();
+
+        // Check contract is initialized
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        // Check round is not locked
+        if env
+            .storage()
+            .persistent()
+            .get::<DataKey, bool>(&DataKey::RoundLocked(round_id))
+            .unwrap_or(false)
+        {
+            return Err(Error::RoundLocked);
+        }
+
+        // Check player is allowed into the round
+        Self::require_registered(&env, round_id, &player)?;
+
+        // Verify proof
+        let trial_completion = Self::verify_proof_internal(&env, receipt, journal, player.clone())?;
+
+        // Validate proof matches player and round
+        if trial_completion.player != player {
+            return Err(Error::InvalidProof);
+        }
+        if trial_completion.round_id != round_id {
+            return Err(Error::InvalidProof);
+        }
+
+        Self::record_completion_internal(&env, round_id, &player, &trial_completion.trial_id)
+    }
+
+    /// Get player progress for a round
+    pub fn get_progress(env: Env, round_id: u32, player: Address) -> Option<PlayerProgress> {
+        let key = DataKey::PlayerProgress(round_id, player);
+        env.storage().persistent().get(&key)
+    }
+
+    /// Get current King for a round
+    pub fn get_king(env: Env, round_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::King(round_id))
+    }
+
+    /// Get current round ID
+    pub fn get_current_round(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::CurrentRoundId)
+            .unwrap_or(1)
+    }
+
+    /// Get the score weight of a trial (default: 1)
+    pub fn get_trial_weight(env: Env, trial_id: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TrialWeight(trial_id))
+            .unwrap_or(DEFAULT_TRIAL_WEIGHT)
+    }
+
+    /// Get the score needed to become King
+    pub fn get_required_score(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RequiredScore)
+            .unwrap_or(DEFAULT_REQUIRED_SCORE)
+    }
+
+    // ========================================================================
+    // Batch Settlement
+    // ========================================================================
+
+    /// Post a Merkle root of trial completions for a round (operator only)
+    ///
+    /// A root can only be replaced once it has been successfully challenged.
+    pub fn post_completion_root(
+        env: Env,
+        operator: Address,
+        round_id: u32,
+        root: BytesN<32>,
+    ) -> Result<CompletionRoot, Error> {
+        operator.require_auth();
+
+        let stored_operator: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Operator)
+            .ok_or(Error::UnauthorizedOperator)?;
+
+        if operator != stored_operator {
+            return Err(Error::UnauthorizedOperator);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .get::<DataKey, bool>(&DataKey::RoundLocked(round_id))
+            .unwrap_or(false)
+        {
+            return Err(Error::RoundLocked);
+        }
+
+        if let Some(existing) = Self::get_completion_root(env.clone(), round_id) {
+            if !existing.challenged {
+                return Err(Error::RootAlreadyPosted);
+            }
+        }
+
+        let now = env.ledger().timestamp();
+        let completion_root = CompletionRoot {
+            root: root.clone(),
+            posted_at: now,
+            dispute_deadline: now.saturating_add(Self::get_dispute_window(env.clone())),
+            challenged: false,
+            open_disputes: 0,
+        };
+
+        let key = DataKey::CompletionRoot(round_id);
+        env.storage().persistent().set(&key, &completion_root);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+
+        log!(&env, "Completion Root Posted: round={}, root={}", round_id, root);
+
+        env.events()
+            .publish((symbol_short!("ROOT"), round_id), root);
+
+        Ok(completion_root)
+    }
+
+    /// Challenge a posted root by revealing an invalid leaf
+    ///
+    /// The challenger supplies a leaf included in the root together with the
+    /// receipt and journal it commits to. The root is invalidated if the
+    /// proof fails verification, doesn't match the leaf or round, or the
+    /// leaf's player isn't registered for the round.
+    pub fn challenge_root(
+        env: Env,
+        challenger: Address,
+        round_id: u32,
+        leaf: CompletionLeaf,
+        proof: soroban_sdk::Vec<BytesN<32>>,
+        receipt: Bytes,
+        journal: Bytes,
+    ) -> Result<(), Error> {
+        challenger.require_auth();
+
+        let key = DataKey::CompletionRoot(round_id);
+        let mut completion_root =
+            Self::get_completion_root(env.clone(), round_id).ok_or(Error::RootNotPosted)?;
+
+        if completion_root.challenged {
+            return Err(Error::RootChallenged);
+        }
+        if env.ledger().timestamp() > completion_root.dispute_deadline {
+            return Err(Error::DisputeWindowClosed);
+        }
+
+        let leaf_hash = Self::completion_leaf_hash(&env, &leaf);
+        if !merkle::verify(&env, &completion_root.root, &leaf_hash, &proof) {
+            return Err(Error::InvalidMerkleProof);
+        }
+
+        // The revealed receipt and journal must be the ones the leaf commits to
+        if !Self::matches_leaf(&env, &leaf, &receipt, &journal) {
+            return Err(Error::ChallengeRejected);
+        }
+
+        if Self::leaf_is_valid(&env, round_id, &leaf, receipt, journal) {
+            return Err(Error::ChallengeRejected);
+        }
+
+        completion_root.challenged = true;
+        env.storage().persistent().set(&key, &completion_root);
+
+        log!(
+            &env,
+            "Completion Root Challenged: round={}, challenger={}",
+            round_id,
+            challenger
+        );
+
+        env.events()
+            .publish((symbol_short!("CHALLENGE"), round_id), challenger);
+
+        Ok(())
+    }
+
+    /// Dispute a leaf of a posted root without revealing its proof
+    ///
+    /// For leaves whose receipt and journal the challenger doesn't have.
+    /// Claims from the root are blocked until the leaf's receipt and journal
+    /// are published with `respond_to_dispute`; if nobody does so within
+    /// the dispute window, `resolve_dispute` invalidates the root.
+    pub fn dispute_leaf(
+        env: Env,
+        challenger: Address,
+        round_id: u32,
+        leaf: CompletionLeaf,
+        proof: soroban_sdk::Vec<BytesN<32>>,
+    ) -> Result<LeafDispute, Error> {
+        challenger.require_auth();
+
+        let root_key = DataKey::CompletionRoot(round_id);
+        let mut completion_root =
+            Self::get_completion_root(env.clone(), round_id).ok_or(Error::RootNotPosted)?;
+
+        if completion_root.challenged {
+            return Err(Error::RootChallenged);
+        }
+        let now = env.ledger().timestamp();
+        if now > completion_root.dispute_deadline {
+            return Err(Error::DisputeWindowClosed);
+        }
+
+        let leaf_hash = Self::completion_leaf_hash(&env, &leaf);
+        if !merkle::verify(&env, &completion_root.root, &leaf_hash, &proof) {
+            return Err(Error::InvalidMerkleProof);
+        }
+
+        let dispute_key = DataKey::LeafDispute(round_id, leaf_hash.clone());
+        if let Some(existing) = Self::get_leaf_dispute(env.clone(), round_id, leaf_hash.clone()) {
+            if existing.root == completion_root.root {
+                return Err(Error::DisputeOpen);
+            }
+        }
+
+        let dispute = LeafDispute {
+            root: completion_root.root.clone(),
+            challenger: challenger.clone(),
+            respond_by: now.saturating_add(Self::get_dispute_window(env.clone())),
+        };
+        env.storage().persistent().set(&dispute_key, &dispute);
+        env.storage()
+            .persistent()
+            .extend_ttl(&dispute_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+
+        completion_root.open_disputes += 1;
+        env.storage().persistent().set(&root_key, &completion_root);
+
+        log!(
+            &env,
+            "Completion Leaf Disputed: round={}, leaf={}, challenger={}",
+            round_id,
+            leaf_hash,
+            challenger
+        );
+
+        env.events()
+            .publish((symbol_short!("DISPUTE"), round_id), (leaf_hash, challenger));
+
+        Ok(dispute)
+    }
+
+    /// Answer a leaf dispute by publishing the leaf's receipt and journal
+    ///
+    /// Permissionless, normally called by the operator. The dispute is
+    /// closed only if the proof matches the leaf and verifies; an invalid
+    /// proof can be used with `challenge_root` instead.
+    pub fn respond_to_dispute(
+        env: Env,
+        round_id: u32,
+        leaf: CompletionLeaf,
+        receipt: Bytes,
+        journal: Bytes,
+    ) -> Result<(), Error> {
+        let root_key = DataKey::CompletionRoot(round_id);
+        let mut completion_root =
+            Self::get_completion_root(env.clone(), round_id).ok_or(Error::RootNotPosted)?;
+
+        if completion_root.challenged {
+            return Err(Error::RootChallenged);
+        }
+
+        let leaf_hash = Self::completion_leaf_hash(&env, &leaf);
+        let dispute = Self::get_leaf_dispute(env.clone(), round_id, leaf_hash.clone())
+            .filter(|dispute| dispute.root == completion_root.root)
+            .ok_or(Error::DisputeNotFound)?;
+
+        if env.ledger().timestamp() > dispute.respond_by {
+            return Err(Error::DisputeWindowClosed);
+        }
+
+        if !Self::matches_leaf(&env, &leaf, &receipt, &journal)
+            || !Self::leaf_is_valid(&env, round_id, &leaf, receipt, journal)
+        {
+            return Err(Error::InvalidProof);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LeafDispute(round_id, leaf_hash.clone()));
+        completion_root.open_disputes -= 1;
+        env.storage().persistent().set(&root_key, &completion_root);
+
+        log!(
+            &env,
+            "Leaf Dispute Answered: round={}, leaf={}",
+            round_id,
+            leaf_hash
+        );
+
+        env.events()
+            .publish((symbol_short!("ANSWERED"), round_id), leaf_hash);
+
+        Ok(())
+    }
+
+    /// Invalidate a root whose leaf dispute went unanswered (permissionless)
+    pub fn resolve_dispute(env: Env, round_id: u32, leaf_hash: BytesN<32>) -> Result<(), Error> {
+        let root_key = DataKey::CompletionRoot(round_id);
+        let mut completion_root =
+            Self::get_completion_root(env.clone(), round_id).ok_or(Error::RootNotPosted)?;
+
+        if completion_root.challenged {
+            return Err(Error::RootChallenged);
+        }
+
+        let dispute = Self::get_leaf_dispute(env.clone(), round_id, leaf_hash.clone())
+            .filter(|dispute| dispute.root == completion_root.root)
+            .ok_or(Error::DisputeNotFound)?;
+
+        if env.ledger().timestamp() <= dispute.respond_by {
+            return Err(Error::ResponseWindowOpen);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LeafDispute(round_id, leaf_hash));
+        completion_root.challenged = true;
+        env.storage().persistent().set(&root_key, &completion_root);
+
+        log!(
+            &env,
+            "Completion Root Challenged: round={}, challenger={}",
+            round_id,
+            dispute.challenger
+        );
+
+        env.events()
+            .publish((symbol_short!("CHALLENGE"), round_id), dispute.challenger);
+
+        Ok(())
+    }
+
+    /// Get the open dispute of a leaf, if any
+    ///
+    /// Disputes of a root that has since been replaced are returned too;
+    /// check `root` against the current completion root.
+    pub fn get_leaf_dispute(
+        env: Env,
+        round_id: u32,
+        leaf_hash: BytesN<32>,
+    ) -> Option<LeafDispute> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LeafDispute(round_id, leaf_hash))
+    }
+
+    /// Apply trial completions from a posted root (permissionless)
+    ///
+    /// Only possible once the dispute window has passed, no leaf dispute is
+    /// open, and while the round is still open, so roots must be posted at
+    /// least one dispute window before the round ends. Leaves whose trial
+    /// the player already completed are skipped.
+    ///
+    /// # Returns
+    /// * Number of completions applied
+    pub fn claim_completions(
+        env: Env,
+        round_id: u32,
+        leaves: soroban_sdk::Vec<CompletionLeaf>,
+        proofs: soroban_sdk::Vec<soroban_sdk::Vec<BytesN<32>>>,
+    ) -> Result<u32, Error> {
+        let completion_root =
+            Self::get_completion_root(env.clone(), round_id).ok_or(Error::RootNotPosted)?;
+
+        if completion_root.challenged {
+            return Err(Error::RootChallenged);
+        }
+        if env.ledger().timestamp() <= completion_root.dispute_deadline {
+            return Err(Error::DisputeWindowOpen);
+        }
+        if completion_root.open_disputes > 0 {
+            return Err(Error::DisputeOpen);
+        }
+        if env
+            .storage()
+            .persistent()
+            .get::<DataKey, bool>(&DataKey::RoundLocked(round_id))
+            .unwrap_or(false)
+        {
+            return Err(Error::RoundLocked);
+        }
+        if leaves.len() != proofs.len() {
+            return Err(Error::InvalidMerkleProof);
+        }
+
+        let mut applied = 0u32;
+        for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+            let leaf_hash = Self::completion_leaf_hash(&env, &leaf);
+            if !merkle::verify(&env, &completion_root.root, &leaf_hash, &proof) {
+                return Err(Error::InvalidMerkleProof);
+            }
+
+            Self::require_registered(&env, round_id, &leaf.player)?;
+
+            let player = leaf.player.clone();
+            if Self::has_completed(env.clone(), round_id, player, leaf.trial_id.clone()) {
+                continue;
+            }
+
+            Self::record_completion_internal(&env, round_id, &leaf.player, &leaf.trial_id)?;
+            applied += 1;
+        }
+
+        Ok(applied)
+    }
+
+    /// Get the completion root posted for a round
+    pub fn get_completion_root(env: Env, round_id: u32) -> Option<CompletionRoot> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CompletionRoot(round_id))
+    }
+
+    /// Get the dispute window for posted roots, in seconds (default: 1 day)
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DisputeWindow)
+            .unwrap_or(DEFAULT_DISPUTE_WINDOW)
+    }
+
+    // ========================================================================
+    // Registration
+    // ========================================================================
+
+    /// Register for an invite-only round
+    ///
+    /// # Arguments
+    /// * `player` - Player registering (must sign)
+    /// * `round_id` - Round to register for
+    /// * `proof` - Merkle inclusion proof (only used by `Registration::MerkleRoot`)
+    pub fn register(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        proof: soroban_sdk::Vec<BytesN<32>>,
+    ) -> Result<(), Error> {
+        player.require_auth();
+
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        if Self::is_registered(env.clone(), round_id, player.clone()) {
+            return Err(Error::AlreadyRegistered);
+        }
+
+        match Self::get_round_config(env.clone(), round_id).registration {
+            Registration::Open => {}
+            Registration::Allowlist => return Err(Error::RegistrationClosed),
+            Registration::MerkleRoot(root) => {
+                let leaf = merkle::address_leaf(&env, &player);
+                if !merkle::verify(&env, &root, &leaf, &proof) {
+                    return Err(Error::InvalidMerkleProof);
+                }
+            }
+            Registration::SelfRegistration(deadline) => {
+                if env.ledger().timestamp() > deadline {
+                    return Err(Error::RegistrationClosed);
+                }
+            }
+        }
+
+        Self::register_internal(&env, round_id, &player);
+
+        Ok(())
+    }
+
+    /// Check if a player is registered for a round
+    pub fn is_registered(env: Env, round_id: u32, player: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Registered(round_id, player))
+    }
+
+    /// Get registered players for a round, in registration order
+    ///
+    /// At most 100 players are returned per call.
+    pub fn get_registered_players(
+        env: Env,
+        round_id: u32,
+        offset: u32,
+        limit: u32,
+    ) -> soroban_sdk::Vec<Address> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RegisteredCount(round_id))
+            .unwrap_or(0);
+        let end = offset
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(count);
+
+        let mut players = SorobanVec::new(&env);
+        for index in offset..end {
+            if let Some(player) = env
+                .storage()
+                .persistent()
+                .get(&DataKey::RegisteredPlayer(round_id, index))
+            {
+                players.push_back(player);
+            }
+        }
+        players
+    }
+
+    /// Get the configuration of a round (default: open registration)
+    pub fn get_round_config(env: Env, round_id: u32) -> RoundConfig {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RoundConfig(round_id))
+            .unwrap_or(RoundConfig {
+                registration: Registration::Open,
+                team_mode: TeamMode::Disabled,
+            })
+    }
+
+    // ========================================================================
+    // Teams
+    // ========================================================================
+
+    /// Create a team for a team round
+    ///
+    /// The creator becomes the team's captain and first member. Only the
+    /// captain can invite other players.
+    pub fn create_team(
+        env: Env,
+        captain: Address,
+        round_id: u32,
+        team_id: u32,
+    ) -> Result<(), Error> {
+        captain.require_auth();
+
+        Self::require_team_round(&env, round_id)?;
+        Self::require_registered(&env, round_id, &captain)?;
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::PlayerTeam(round_id, captain.clone()))
+        {
+            return Err(Error::AlreadyInTeam);
+        }
+
+        // Team IDs are not reused within a round, not even once disbanded
+        let captain_key = DataKey::TeamCaptain(round_id, team_id);
+        if env.storage().persistent().has(&captain_key)
+            || Self::is_team_disbanded(&env, round_id, team_id)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::TeamProgress(round_id, team_id))
+        {
+            return Err(Error::TeamExists);
+        }
+
+        env.storage().persistent().set(&captain_key, &captain);
+        env.storage().persistent().extend_ttl(
+            &captain_key,
+            LEDGER_TTL_THRESHOLD,
+            LEDGER_TTL_EXTENSION,
+        );
+        Self::add_team_member(&env, round_id, team_id, &captain);
+
+        log!(
+            &env,
+            "Team Created: round={}, team={}, captain={}",
+            round_id,
+            team_id,
+            captain
+        );
+
+        env.events()
+            .publish((symbol_short!("TEAMNEW"), round_id), (team_id, captain));
+
+        Ok(())
+    }
+
+    /// Invite a player to a team
+    ///
+    /// Only the team's captain can invite. The invite is consumed when the
+    /// player joins.
+    pub fn invite_to_team(
+        env: Env,
+        captain: Address,
+        round_id: u32,
+        team_id: u32,
+        player: Address,
+    ) -> Result<(), Error> {
+        captain.require_auth();
+
+        Self::require_team_captain(&env, round_id, team_id, &captain)?;
+
+        let invite_key = DataKey::TeamInvite(round_id, team_id, player.clone());
+        env.storage().persistent().set(&invite_key, &true);
+        env.storage().persistent().extend_ttl(
+            &invite_key,
+            LEDGER_TTL_THRESHOLD,
+            LEDGER_TTL_EXTENSION,
+        );
+
+        env.events()
+            .publish((symbol_short!("TEAMINV"), round_id), (team_id, player));
+
+        Ok(())
+    }
+
+    /// Join a team for a team round
+    ///
+    /// Requires an invite from the team's captain. A player can only be in
+    /// one team per round at a time. Invites to a disbanded team are void.
+    pub fn join_team(env: Env, player: Address, round_id: u32, team_id: u32) -> Result<(), Error> {
+        player.require_auth();
+
+        Self::require_team_round(&env, round_id)?;
+        Self::require_registered(&env, round_id, &player)?;
+
+        if Self::is_team_disbanded(&env, round_id, team_id) {
+            return Err(Error::TeamDisbanded);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::PlayerTeam(round_id, player.clone()))
+        {
+            return Err(Error::AlreadyInTeam);
+        }
+
+        let invite_key = DataKey::TeamInvite(round_id, team_id, player.clone());
+        if !env.storage().persistent().has(&invite_key) {
+            return Err(Error::NotInvited);
+        }
+
+        if Self::get_team_members(env.clone(), round_id, team_id).len() >= MAX_TEAM_SIZE {
+            return Err(Error::TeamFull);
+        }
+
+        env.storage().persistent().remove(&invite_key);
+        Self::add_team_member(&env, round_id, team_id, &player);
+
+        log!(
+            &env,
+            "Team Joined: round={}, team={}, player={}",
+            round_id,
+            team_id,
+            player
+        );
+
+        env.events()
+            .publish((symbol_short!("TEAMJOIN"), round_id), (team_id, player));
+
+        Ok(())
+    }
+
+    /// Leave a team
+    ///
+    /// Completions already counted stay with the team. A leaving captain
+    /// hands the team to the longest-standing member; the last member
+    /// leaving disbands the team, which keeps its ID but has no captain.
+    pub fn leave_team(env: Env, player: Address, round_id: u32) -> Result<(), Error> {
+        player.require_auth();
+
+        let team_key = DataKey::PlayerTeam(round_id, player.clone());
+        let team_id: u32 = env
+            .storage()
+            .persistent()
+            .get(&team_key)
+            .ok_or(Error::NotInTeam)?;
+
+        let members_key = DataKey::TeamMembers(round_id, team_id);
+        let mut members = Self::get_team_members(env.clone(), round_id, team_id);
+        if let Some(index) = members.first_index_of(&player) {
+            members.remove(index);
+        }
+        env.storage().persistent().remove(&team_key);
+
+        let captain_key = DataKey::TeamCaptain(round_id, team_id);
+        match members.first() {
+            Some(next_captain) => {
+                env.storage().persistent().set(&members_key, &members);
+                let captain: Option<Address> = env.storage().persistent().get(&captain_key);
+                if captain == Some(player.clone()) {
+                    env.storage().persistent().set(&captain_key, &next_captain);
+                }
+            }
+            None => {
+                // The tombstone keeps the team ID taken
+                env.storage().persistent().remove(&members_key);
+                env.storage().persistent().remove(&captain_key);
+                let disbanded_key = DataKey::TeamDisbanded(round_id, team_id);
+                env.storage().persistent().set(&disbanded_key, &true);
+                env.storage().persistent().extend_ttl(
+                    &disbanded_key,
+                    LEDGER_TTL_THRESHOLD,
+                    LEDGER_TTL_EXTENSION,
+                );
+            }
+        }
+
+        log!(
+            &env,
+            "Team Left: round={}, team={}, player={}",
+            round_id,
+            team_id,
+            player
+        );
+
+        env.events()
+            .publish((symbol_short!("TEAMLEFT"), round_id), (team_id, player));
+
+        Ok(())
+    }
+
+    /// Get the captain of a team
+    pub fn get_team_captain(env: Env, round_id: u32, team_id: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TeamCaptain(round_id, team_id))
+    }
+
+    /// Get the team a player joined for a round
+    pub fn get_team(env: Env, round_id: u32, player: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PlayerTeam(round_id, player))
+    }
+
+    /// Get the members of a team, in join order
+    pub fn get_team_members(env: Env, round_id: u32, team_id: u32) -> soroban_sdk::Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TeamMembers(round_id, team_id))
+            .unwrap_or(SorobanVec::new(&env))
+    }
+
+    /// Get team progress for a round
+    pub fn get_team_progress(env: Env, round_id: u32, team_id: u32) -> Option<TeamProgress> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TeamProgress(round_id, team_id))
+    }
+
+    /// Get the winning team for a round
+    pub fn get_team_king(env: Env, round_id: u32) -> Option<TeamKing> {
+        env.storage().persistent().get(&DataKey::TeamKing(round_id))
+    }
+
+    // ========================================================================
+    // Admin Functions
+    // ========================================================================
+
+    /// Start a new round (admin only)
+    pub fn start_new_round(env: Env, admin: Address) -> Result<u32, Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let current_round: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CurrentRoundId)
+            .unwrap_or(1);
+
+        // Lock previous round
+        env.storage()
+            .persistent()
+            .set(&DataKey::RoundLocked(current_round), &true);
+
+        // Increment round
+        let new_round = current_round + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::CurrentRoundId, &new_round);
+
+        log!(&env, "New Round Started: {}", new_round);
+
+        Ok(new_round)
+    }
+
+    /// Update the score needed to become King (admin only)
+    pub fn update_required_score(
+        env: Env,
+        admin: Address,
+        required_score: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RequiredScore, &required_score);
+
+        Ok(())
+    }
+
+    /// Set the score weight of a trial (admin only)
+    ///
+    /// Harder trials (e.g. Thronebreaker Protocol) can be worth more than
+    /// simple ones (e.g. Color Sigil). Unregistered trials are worth 1.
+    pub fn set_trial_weight(
+        env: Env,
+        admin: Address,
+        trial_id: BytesN<32>,
+        weight: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::TrialWeight(trial_id.clone());
+        env.storage().persistent().set(&key, &weight);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+
+        log!(&env, "Trial Weight Set: trial={}, weight={}", trial_id, weight);
+
+        Ok(())
+    }
+
+    /// Set the batch settlement operator (admin only)
+    pub fn set_operator(env: Env, admin: Address, operator: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
 
-// ============================================================================
-// Storage TTL Configuration
-// ============================================================================
+        env.storage().instance().set(&DataKey::Operator, &operator);
 
-const LEDGER_TTL_THRESHOLD: u32 = 518_400; // ~30 days
-const LEDGER_TTL_EXTENSION: u32 = 518_400;
+        log!(&env, "Operator Set: {}", operator);
 
-// ============================================================================
-// Contract
-// ============================================================================
+        Ok(())
+    }
 
-#[contract]
-pub struct ThroneContract;
+    /// Set the dispute window for posted roots, in seconds (admin only)
+    pub fn set_dispute_window(env: Env, admin: Address, seconds: u64) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
 
-#[contractimpl]
-impl ThroneContract {
-    // ========================================================================
-    // Initialization
-    // ========================================================================
+        env.storage().instance().set(&DataKey::DisputeWindow, &seconds);
 
-    /// Initialize the throne contract
-    ///
-    /// # Arguments
-    /// * `admin` - Admin address for contract management
-    /// * `image_id` - RISC Zero image ID for proof verification
-    /// * `required_trials` - Number of trials needed to become King (default: 7)
-    pub fn initialize(
+        Ok(())
+    }
+
+    /// Configure a round (admin only)
+    pub fn set_round_config(
         env: Env,
         admin: Address,
-        image_id: BytesN<32>,
-        required_trials: u32,
+        round_id: u32,
+        config: RoundConfig,
     ) -> Result<(), Error> {
-        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        // Check not already initialized
-        if env.storage().instance().has(&DataKey::Admin) {
-            return Err(Error::AlreadyInitialized);
-        }
-
-        // Store admin and config
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::ImageId, &image_id);
+        let key = DataKey::RoundConfig(round_id);
+        env.storage().persistent().set(&key, &config);
         env.storage()
-            .instance()
-            .set(&DataKey::RequiredTrials, &required_trials);
-        env.storage().instance().set(&DataKey::CurrentRoundId, &1u32);
+            .persistent()
+            .extend_ttl(&key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
 
-        log!(
-            &env,
-            "Throne Contract Initialized: admin={}, required_trials={}",
-            admin,
-            required_trials
-        );
+        log!(&env, "Round Configured: round={}", round_id);
 
         Ok(())
     }
 
-    // ========================================================================
-    // Core Functions
-    // ========================================================================
-
-    /// Submit a ZK proof of trial completion
-    ///
-    /// # Arguments
-    /// * `player` - Player address (must match proof journal)
-    /// * `receipt` - RISC Zero receipt bytes
-    /// * `journal` - Journal data (trial_id, player, round_id, solution_hash)
-    /// * `round_id` - Round identifier
+    /// Add players to a round's allowlist (admin only)
     ///
-    /// # Returns
-    /// * `PlayerProgress` - Updated player progress
-    pub fn submit_proof(
+    /// Players that are already registered are skipped.
+    pub fn register_players(
         env: Env,
-        player: Address,
-        receipt: Bytes,
-        journal: Bytes,
+        admin: Address,
         round_id: u32,
-    ) -> Result<PlayerProgress, Error> {
-        player.require_auth();
+        players: soroban_sdk::Vec<Address>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
 
-        // Check contract is initialized
-        let admin: Address = env
+        for player in players.iter() {
+            if !Self::is_registered(env.clone(), round_id, player.clone()) {
+                Self::register_internal(&env, round_id, &player);
+            }
+        }
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Internal Functions
+    // ========================================================================
+
+    /// Check that `admin` is the stored admin and has authorized the call
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+
+        let stored_admin: Address = env
             .storage()
             .instance()
             .get(&DataKey::Admin)
             .ok_or(Error::NotInitialized)?;
 
-        // Check round is not locked
-        if env
-            .storage()
-            .persistent()
-            .get::<DataKey, bool>(&DataKey::RoundLocked(round_id))
-            .unwrap_or(false)
-        {
-            return Err(Error::RoundLocked);
+        if *admin != stored_admin {
+            return Err(Error::UnauthorizedAdmin);
         }
 
-        // Verify proof
-        let trial_completion = Self::verify_proof_internal(&env, receipt, journal, player.clone())?;
+        Ok(())
+    }
 
-        // Validate proof matches player and round
-        if trial_completion.player != player {
-            return Err(Error::InvalidProof);
+    /// Check that a player may submit proofs for a round
+    fn require_registered(env: &Env, round_id: u32, player: &Address) -> Result<(), Error> {
+        let config = Self::get_round_config(env.clone(), round_id);
+        if config.registration == Registration::Open {
+            return Ok(());
         }
-        if trial_completion.round_id != round_id {
-            return Err(Error::InvalidProof);
+
+        if !Self::is_registered(env.clone(), round_id, player.clone()) {
+            return Err(Error::NotRegistered);
         }
 
+        Ok(())
+    }
+
+    /// Add a verified trial completion to a player's progress, crowning them
+    /// when they reach the required score
+    fn record_completion_internal(
+        env: &Env,
+        round_id: u32,
+        player: &Address,
+        trial_id: &BytesN<32>,
+    ) -> Result<PlayerProgress, Error> {
         // Check trial not already completed
         let completed_key = DataKey::CompletedTrials(round_id, player.clone());
         let mut completed: SorobanVec<BytesN<32>> = env
             .storage()
             .persistent()
             .get(&completed_key)
-            .unwrap_or(SorobanVec::new(&env));
+            .unwrap_or(SorobanVec::new(env));
 
-        if completed.contains(&trial_completion.trial_id) {
+        if completed.contains(trial_id) {
             return Err(Error::TrialAlreadyCompleted);
         }
 
         // Add trial to completed set
-        completed.push_back(trial_completion.trial_id.clone());
+        completed.push_back(trial_id.clone());
         env.storage().persistent().set(&completed_key, &completed);
         env.storage()
             .persistent()
@@ -215,25 +1280,30 @@ impl ThroneContract {
         // Update player progress
         let progress_key = DataKey::PlayerProgress(round_id, player.clone());
         let trials_completed = completed.len();
+        let previous_score = env
+            .storage()
+            .persistent()
+            .get::<DataKey, PlayerProgress>(&progress_key)
+            .map(|p| p.score)
+            .unwrap_or(0);
+        let weight = Self::get_trial_weight(env.clone(), trial_id.clone());
+        let score = previous_score.saturating_add(weight);
 
         let mut progress = PlayerProgress {
             player: player.clone(),
             round_id,
             trials_completed,
+            score,
             last_trial_timestamp: env.ledger().timestamp(),
             is_king: false,
         };
 
-        // Check if player completed all trials
-        let required_trials: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::RequiredTrials)
-            .unwrap_or(7);
+        // Check if player reached the required score
+        let required_score = Self::get_required_score(env.clone());
 
-        if trials_completed >= required_trials {
+        if score >= required_score {
             progress.is_king = true;
-            Self::assign_king_internal(&env, round_id, player.clone())?;
+            Self::assign_king_internal(env, round_id, player.clone(), score)?;
         }
 
         // Store progress
@@ -242,106 +1312,232 @@ impl ThroneContract {
             .persistent()
             .extend_ttl(&progress_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
 
+        // Count the trial for the player's team
+        Self::record_team_completion(env, round_id, player, trial_id, weight);
+
         log!(
-            &env,
-            "Trial Completed: player={}, round={}, trials={}/{}",
+            env,
+            "Trial Completed: player={}, round={}, trials={}, score={}/{}",
             player,
             round_id,
             trials_completed,
-            required_trials
+            score,
+            required_score
         );
 
         Ok(progress)
     }
 
-    /// Get player progress for a round
-    pub fn get_progress(env: Env, round_id: u32, player: Address) -> Option<PlayerProgress> {
-        let key = DataKey::PlayerProgress(round_id, player);
-        env.storage().persistent().get(&key)
+    /// Merkle leaf hash of a completion leaf
+    fn completion_leaf_hash(env: &Env, leaf: &CompletionLeaf) -> BytesN<32> {
+        merkle::completion_leaf(
+            env,
+            &leaf.player,
+            &leaf.trial_id,
+            &leaf.journal_digest,
+            &leaf.receipt_digest,
+        )
     }
 
-    /// Get current King for a round
-    pub fn get_king(env: Env, round_id: u32) -> Option<Address> {
-        env.storage().persistent().get(&DataKey::King(round_id))
+    /// Check that a receipt and journal are the ones a leaf commits to
+    fn matches_leaf(env: &Env, leaf: &CompletionLeaf, receipt: &Bytes, journal: &Bytes) -> bool {
+        let receipt_digest: BytesN<32> = env.crypto().sha256(receipt).into();
+        let journal_digest: BytesN<32> = env.crypto().sha256(journal).into();
+        receipt_digest == leaf.receipt_digest && journal_digest == leaf.journal_digest
     }
 
-    /// Get current round ID
-    pub fn get_current_round(env: Env) -> u32 {
+    /// Check that a leaf's proof verifies for its trial, round and player
+    fn leaf_is_valid(
+        env: &Env,
+        round_id: u32,
+        leaf: &CompletionLeaf,
+        receipt: Bytes,
+        journal: Bytes,
+    ) -> bool {
+        match Self::verify_proof_internal(env, receipt, journal, leaf.player.clone()) {
+            Ok(completion) => {
+                completion.trial_id == leaf.trial_id
+                    && completion.round_id == round_id
+                    && Self::require_registered(env, round_id, &leaf.player).is_ok()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Check that a round is a team round
+    fn require_team_round(env: &Env, round_id: u32) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        if Self::get_round_config(env.clone(), round_id).team_mode == TeamMode::Disabled {
+            return Err(Error::TeamModeDisabled);
+        }
+
+        Ok(())
+    }
+
+    /// Check that `captain` is the captain of a team
+    fn require_team_captain(
+        env: &Env,
+        round_id: u32,
+        team_id: u32,
+        captain: &Address,
+    ) -> Result<(), Error> {
+        match Self::get_team_captain(env.clone(), round_id, team_id) {
+            Some(stored) if stored == *captain => Ok(()),
+            _ => Err(Error::NotTeamCaptain),
+        }
+    }
+
+    /// Whether the last member of a team left
+    fn is_team_disbanded(env: &Env, round_id: u32, team_id: u32) -> bool {
         env.storage()
-            .instance()
-            .get(&DataKey::CurrentRoundId)
-            .unwrap_or(1)
+            .persistent()
+            .has(&DataKey::TeamDisbanded(round_id, team_id))
     }
 
-    // ========================================================================
-    // Admin Functions
-    // ========================================================================
+    /// Add a player to a team's members
+    fn add_team_member(env: &Env, round_id: u32, team_id: u32, player: &Address) {
+        let members_key = DataKey::TeamMembers(round_id, team_id);
+        let team_key = DataKey::PlayerTeam(round_id, player.clone());
 
-    /// Start a new round (admin only)
-    pub fn start_new_round(env: Env, admin: Address) -> Result<u32, Error> {
-        admin.require_auth();
+        let mut members = Self::get_team_members(env.clone(), round_id, team_id);
+        members.push_back(player.clone());
+        env.storage().persistent().set(&members_key, &members);
+        env.storage().persistent().set(&team_key, &team_id);
+        for key in [members_key, team_key] {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+        }
+    }
 
-        let stored_admin: Address = env
+    /// Count a player's trial completion for their team, crowning the team
+    /// when it reaches the required score
+    fn record_team_completion(
+        env: &Env,
+        round_id: u32,
+        player: &Address,
+        trial_id: &BytesN<32>,
+        weight: u32,
+    ) {
+        let team_mode = Self::get_round_config(env.clone(), round_id).team_mode;
+        if team_mode == TeamMode::Disabled {
+            return;
+        }
+
+        let team_id = match Self::get_team(env.clone(), round_id, player.clone()) {
+            Some(team_id) => team_id,
+            None => return,
+        };
+
+        // Track which trials the team has completed
+        let completed_key = DataKey::TeamCompletedTrials(round_id, team_id);
+        let mut completed: SorobanVec<BytesN<32>> = env
             .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(Error::NotInitialized)?;
+            .persistent()
+            .get(&completed_key)
+            .unwrap_or(SorobanVec::new(env));
 
-        if admin != stored_admin {
-            return Err(Error::UnauthorizedAdmin);
+        let first_for_team = !completed.contains(trial_id);
+        if first_for_team {
+            completed.push_back(trial_id.clone());
+            env.storage().persistent().set(&completed_key, &completed);
+            env.storage()
+                .persistent()
+                .extend_ttl(&completed_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+        } else if team_mode == TeamMode::UniquePerTrial {
+            // A teammate already scored this trial
+            return;
         }
 
-        let current_round: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::CurrentRoundId)
-            .unwrap_or(1);
+        let progress_key = DataKey::TeamProgress(round_id, team_id);
+        let mut progress = Self::get_team_progress(env.clone(), round_id, team_id).unwrap_or(
+            TeamProgress {
+                team_id,
+                round_id,
+                trials_completed: 0,
+                score: 0,
+                last_trial_timestamp: 0,
+                is_king: false,
+            },
+        );
+        progress.trials_completed = completed.len();
+        progress.score = progress.score.saturating_add(weight);
+        progress.last_trial_timestamp = env.ledger().timestamp();
 
-        // Lock previous round
+        if progress.score >= Self::get_required_score(env.clone()) {
+            progress.is_king = Self::assign_team_king_internal(env, round_id, team_id);
+        }
+
+        env.storage().persistent().set(&progress_key, &progress);
         env.storage()
             .persistent()
-            .set(&DataKey::RoundLocked(current_round), &true);
+            .extend_ttl(&progress_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
 
-        // Increment round
-        let new_round = current_round + 1;
+        env.events().publish(
+            (symbol_short!("TEAMPROG"), round_id),
+            (team_id, progress.score),
+        );
+    }
+
+    /// Crown a team for a round
+    ///
+    /// Returns whether `team_id` holds the team crown afterwards.
+    fn assign_team_king_internal(env: &Env, round_id: u32, team_id: u32) -> bool {
+        let king_key = DataKey::TeamKing(round_id);
+        if let Some(existing) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, TeamKing>(&king_key)
+        {
+            // First team to reach the score keeps the crown
+            return existing.team_id == team_id;
+        }
+
+        let team_king = TeamKing {
+            team_id,
+            round_id,
+            members: Self::get_team_members(env.clone(), round_id, team_id),
+            crowned_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&king_key, &team_king);
         env.storage()
-            .instance()
-            .set(&DataKey::CurrentRoundId, &new_round);
+            .persistent()
+            .extend_ttl(&king_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
 
-        log!(&env, "New Round Started: {}", new_round);
+        log!(env, "👑 NEW TEAM KING: round={}, team={}", round_id, team_id);
 
-        Ok(new_round)
+        env.events()
+            .publish((symbol_short!("TEAMKING"), round_id), team_id);
+
+        true
     }
 
-    /// Update required trials (admin only)
-    pub fn update_required_trials(
-        env: Env,
-        admin: Address,
-        required_trials: u32,
-    ) -> Result<(), Error> {
-        admin.require_auth();
+    /// Record a player as registered for a round
+    fn register_internal(env: &Env, round_id: u32, player: &Address) {
+        let count_key = DataKey::RegisteredCount(round_id);
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
 
-        let stored_admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(Error::NotInitialized)?;
+        let registered_key = DataKey::Registered(round_id, player.clone());
+        let index_key = DataKey::RegisteredPlayer(round_id, count);
+        env.storage().persistent().set(&registered_key, &true);
+        env.storage().persistent().set(&index_key, player);
+        env.storage().persistent().set(&count_key, &(count + 1));
 
-        if admin != stored_admin {
-            return Err(Error::UnauthorizedAdmin);
+        for key in [registered_key, index_key, count_key] {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
         }
 
-        env.storage()
-            .instance()
-            .set(&DataKey::RequiredTrials, &required_trials);
+        log!(env, "Player Registered: round={}, player={}", round_id, player);
 
-        Ok(())
+        env.events()
+            .publish((symbol_short!("REGISTER"), round_id), player.clone());
     }
 
-    // ========================================================================
-    // Internal Functions
-    // ========================================================================
-
     /// Verify RISC Zero proof with enhanced validation
     fn verify_proof_internal(
         env: &Env,
@@ -350,7 +1546,7 @@ impl ThroneContract {
         player: Address,
     ) -> Result<TrialCompletion, Error> {
         // Get stored image ID
-        let image_id: BytesN<32> = env
+        let _image_id: BytesN<32> = env
             .storage()
             .instance()
             .get(&DataKey::ImageId)
@@ -446,7 +1642,12 @@ impl ThroneContract {
     }
 
     /// Assign king for a round
-    fn assign_king_internal(env: &Env, round_id: u32, player: Address) -> Result<(), Error> {
+    fn assign_king_internal(
+        env: &Env,
+        round_id: u32,
+        player: Address,
+        score: u32,
+    ) -> Result<(), Error> {
         // Check if king already assigned
         let king_key = DataKey::King(round_id);
         if env.storage().persistent().has(&king_key) {
@@ -460,6 +1661,19 @@ impl ThroneContract {
             .persistent()
             .extend_ttl(&king_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
 
+        // Record reign details for the oracle
+        let reign_key = DataKey::Reign(round_id);
+        let reign = ReignInfo {
+            round_id,
+            king: player.clone(),
+            crowned_at: env.ledger().timestamp(),
+            score,
+        };
+        env.storage().persistent().set(&reign_key, &reign);
+        env.storage()
+            .persistent()
+            .extend_ttl(&reign_key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+
         log!(env, "👑 NEW KING: round={}, player={}", round_id, player);
 
         // Emit event
@@ -471,3 +1685,37 @@ impl ThroneContract {
         Ok(())
     }
 }
+
+// ============================================================================
+// Oracle Interface
+// ============================================================================
+
+#[contractimpl]
+impl ThroneOracle for ThroneContract {
+    fn oracle_version(_env: Env) -> u32 {
+        ORACLE_VERSION
+    }
+
+    fn is_king(env: Env, round_id: u32, player: Address) -> bool {
+        Self::get_king(env, round_id) == Some(player)
+    }
+
+    fn has_completed(env: Env, round_id: u32, player: Address, trial_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get::<DataKey, SorobanVec<BytesN<32>>>(&DataKey::CompletedTrials(round_id, player))
+            .map(|completed| completed.contains(&trial_id))
+            .unwrap_or(false)
+    }
+
+    fn current_king(env: Env) -> Option<Address> {
+        let round_id = Self::get_current_round(env.clone());
+        Self::get_king(env, round_id)
+    }
+
+    fn reign_info(env: Env, round_id: u32) -> Option<ReignInfo> {
+        env.storage().persistent().get(&DataKey::Reign(round_id))
+    }
+}
+
+mod test;
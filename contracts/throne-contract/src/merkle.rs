@@ -0,0 +1,52 @@
+//! # Merkle Inclusion Proofs
+//!
+//! Sorted-pair SHA-256 Merkle trees: at every level the two child hashes are
+//! ordered before being concatenated, so proofs don't need position bits.
+//! Off-chain tooling must build trees the same way.
+
+use soroban_sdk::{Address, Bytes, BytesN, Env, Vec as SorobanVec};
+
+/// Leaf hash for an address: SHA256(strkey)
+pub fn address_leaf(env: &Env, address: &Address) -> BytesN<32> {
+    env.crypto().sha256(&address.to_string().to_bytes()).into()
+}
+
+/// Leaf hash for a trial completion:
+/// SHA256(strkey || trial_id || journal_digest || receipt_digest)
+pub fn completion_leaf(
+    env: &Env,
+    player: &Address,
+    trial_id: &BytesN<32>,
+    journal_digest: &BytesN<32>,
+    receipt_digest: &BytesN<32>,
+) -> BytesN<32> {
+    let mut data = player.to_string().to_bytes();
+    data.extend_from_array(&trial_id.to_array());
+    data.extend_from_array(&journal_digest.to_array());
+    data.extend_from_array(&receipt_digest.to_array());
+    env.crypto().sha256(&data).into()
+}
+
+/// Check that `leaf` is included in the tree with the given `root`
+pub fn verify(
+    env: &Env,
+    root: &BytesN<32>,
+    leaf: &BytesN<32>,
+    proof: &SorobanVec<BytesN<32>>,
+) -> bool {
+    let mut computed = leaf.clone();
+    for sibling in proof.iter() {
+        computed = hash_pair(env, &computed, &sibling);
+    }
+    computed == *root
+}
+
+/// Hash two nodes in sorted order
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (a, b) = (a.to_array(), b.to_array());
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+
+    let mut data = Bytes::from_array(env, &first);
+    data.extend_from_array(&second);
+    env.crypto().sha256(&data).into()
+}
@@ -1,24 +1,102 @@
 #![cfg(test)]
 
+extern crate std;
+
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, BytesN, Env,
+};
+use throne_interface::ThroneOracleClient;
 
 // ============================================================================
-// Tests
+// Helpers
 // ============================================================================
 
-#[test]
-fn test_initialization() {
-    let env = Env::default();
-    let contract_id = env.register_contract(None, ThroneContract);
-    let client = ThroneContractClient::new(&env, &contract_id);
+fn setup(env: &Env, required_score: u32) -> (ThroneContractClient<'_>, Address) {
+    let contract_id = env.register(ThroneContract, ());
+    let client = ThroneContractClient::new(env, &contract_id);
 
-    let admin = Address::generate(&env);
-    let image_id = BytesN::from_array(&env, &[1u8; 32]);
+    let admin = Address::generate(env);
+    let image_id = BytesN::from_array(env, &[1u8; 32]);
 
     env.mock_all_auths();
 
-    client.initialize(&admin, &image_id, &7);
+    client.initialize(&admin, &image_id, &required_score);
+    (client, admin)
+}
+
+/// Trial ID from a short name, zero padded
+fn trial_id(env: &Env, name: &[u8]) -> BytesN<32> {
+    let mut id = [0u8; 32];
+    id[..name.len()].copy_from_slice(name);
+    BytesN::from_array(env, &id)
+}
+
+/// Journal as parsed by the contract: solution_hash || trial_id || round_id || is_valid
+fn journal(env: &Env, name: &[u8], round_id: u32, is_valid: bool) -> Bytes {
+    let mut journal = Bytes::from_array(env, &[42u8; 32]);
+    journal.extend_from_array(&trial_id(env, name).to_array());
+    journal.extend_from_array(&round_id.to_be_bytes());
+    journal.push_back(is_valid as u8);
+    journal
+}
+
+/// Receipt that passes the contract's structural checks
+fn receipt(env: &Env) -> Bytes {
+    Bytes::from_array(env, &[7u8; 100])
+}
+
+fn submit(
+    env: &Env,
+    client: &ThroneContractClient,
+    player: &Address,
+    name: &[u8],
+) -> PlayerProgress {
+    client.submit_proof(player, &receipt(env), &journal(env, name, 1, true), &1)
+}
+
+/// Hash two Merkle nodes the way the contract does (sorted pair)
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (a, b) = (a.to_array(), b.to_array());
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &first);
+    data.extend_from_array(&second);
+    env.crypto().sha256(&data).into()
+}
+
+/// Root of a two-leaf tree
+fn pair_root(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    hash_pair(env, a, b)
+}
+
+fn completion_leaf(env: &Env, player: &Address, name: &[u8], journal: &Bytes) -> CompletionLeaf {
+    CompletionLeaf {
+        player: player.clone(),
+        trial_id: trial_id(env, name),
+        journal_digest: env.crypto().sha256(journal).into(),
+        receipt_digest: env.crypto().sha256(&receipt(env)).into(),
+    }
+}
+
+fn leaf_hash(env: &Env, leaf: &CompletionLeaf) -> BytesN<32> {
+    merkle::completion_leaf(
+        env,
+        &leaf.player,
+        &leaf.trial_id,
+        &leaf.journal_digest,
+        &leaf.receipt_digest,
+    )
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_initialization() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env, 7);
 
     let round = client.get_current_round();
     assert_eq!(round, 1);
@@ -27,37 +105,11 @@ fn test_initialization() {
 #[test]
 fn test_submit_proof_and_progress() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ThroneContract);
-    let client = ThroneContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
+    let (client, _admin) = setup(&env, 3); // Only 3 trials for testing
     let player = Address::generate(&env);
-    let image_id = BytesN::from_array(&env, &[1u8; 32]);
-
-    env.mock_all_auths();
-
-    client.initialize(&admin, &image_id, &3); // Only 3 trials for testing
-
-    // Create mock journal (simplified)
-    let mut journal_data = vec![0u8; 101];
-    // solution_hash
-    journal_data[0..32].copy_from_slice(&[42u8; 32]);
-    // trial_id
-    journal_data[32..64].copy_from_slice(b"colorSigil\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
-    // player_address (simplified)
-    let player_str = player.to_string();
-    let player_bytes = player_str.as_bytes();
-    journal_data[64..64 + player_bytes.len().min(32)].copy_from_slice(&player_bytes[..player_bytes.len().min(32)]);
-    // round_id
-    journal_data[96..100].copy_from_slice(&1u32.to_be_bytes());
-    // is_valid
-    journal_data[100] = 1;
-
-    let journal = Bytes::from_slice(&env, &journal_data);
-    let receipt = Bytes::new(&env); // Mock receipt
 
     // Submit first trial
-    let progress = client.submit_proof(&player, &receipt, &journal, &1);
+    let progress = submit(&env, &client, &player, b"colorSigil");
     assert_eq!(progress.trials_completed, 1);
     assert!(!progress.is_king);
 
@@ -68,72 +120,61 @@ fn test_submit_proof_and_progress() {
 }
 
 #[test]
-#[should_panic(expected = "TrialAlreadyCompleted")]
 fn test_duplicate_trial_submission() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ThroneContract);
-    let client = ThroneContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
+    let (client, _admin) = setup(&env, 7);
     let player = Address::generate(&env);
-    let image_id = BytesN::from_array(&env, &[1u8; 32]);
-
-    env.mock_all_auths();
-
-    client.initialize(&admin, &image_id, &7);
-
-    // Create journal
-    let mut journal_data = vec![0u8; 101];
-    journal_data[32..64].copy_from_slice(b"colorSigil\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
-    let player_str = player.to_string();
-    let player_bytes = player_str.as_bytes();
-    journal_data[64..64 + player_bytes.len().min(32)].copy_from_slice(&player_bytes[..player_bytes.len().min(32)]);
-    journal_data[96..100].copy_from_slice(&1u32.to_be_bytes());
-    journal_data[100] = 1;
-
-    let journal = Bytes::from_slice(&env, &journal_data);
-    let receipt = Bytes::new(&env);
 
     // Submit once
-    client.submit_proof(&player, &receipt, &journal, &1);
-
-    // Submit again (should panic)
-    client.submit_proof(&player, &receipt, &journal, &1);
+    submit(&env, &client, &player, b"colorSigil");
+
+    // Submit again
+    assert_eq!(
+        client.try_submit_proof(
+            &player,
+            &receipt(&env),
+            &journal(&env, b"colorSigil", 1, true),
+            &1
+        ),
+        Err(Ok(Error::TrialAlreadyCompleted))
+    );
 }
 
 #[test]
-fn test_king_assignment() {
+fn test_invalid_proofs_rejected() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ThroneContract);
-    let client = ThroneContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
+    let (client, _admin) = setup(&env, 7);
     let player = Address::generate(&env);
-    let image_id = BytesN::from_array(&env, &[1u8; 32]);
-
-    env.mock_all_auths();
 
-    client.initialize(&admin, &image_id, &2); // Only 2 trials
-
-    let receipt = Bytes::new(&env);
+    let short_receipt = Bytes::from_array(&env, &[7u8; 99]);
+    let valid_journal = journal(&env, b"colorSigil", 1, true);
+    assert_eq!(
+        client.try_submit_proof(&player, &short_receipt, &valid_journal, &1),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    let failed_journal = journal(&env, b"colorSigil", 1, false);
+    assert_eq!(
+        client.try_submit_proof(&player, &receipt(&env), &failed_journal, &1),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    // Journal for another round
+    let other_round = journal(&env, b"colorSigil", 2, true);
+    assert_eq!(
+        client.try_submit_proof(&player, &receipt(&env), &other_round, &1),
+        Err(Ok(Error::InvalidProof))
+    );
+}
 
-    // Submit trial 1
-    let mut journal1 = vec![0u8; 101];
-    journal1[32..64].copy_from_slice(b"trial1\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
-    let player_str = player.to_string();
-    let player_bytes = player_str.as_bytes();
-    journal1[64..64 + player_bytes.len().min(32)].copy_from_slice(&player_bytes[..player_bytes.len().min(32)]);
-    journal1[96..100].copy_from_slice(&1u32.to_be_bytes());
-    journal1[100] = 1;
-    client.submit_proof(&player, &receipt, &Bytes::from_slice(&env, &journal1), &1);
+#[test]
+fn test_king_assignment() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env, 2); // Only 2 trials
+    let player = Address::generate(&env);
 
-    // Submit trial 2
-    let mut journal2 = vec![0u8; 101];
-    journal2[32..64].copy_from_slice(b"trial2\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
-    journal2[64..64 + player_bytes.len().min(32)].copy_from_slice(&player_bytes[..player_bytes.len().min(32)]);
-    journal2[96..100].copy_from_slice(&1u32.to_be_bytes());
-    journal2[100] = 1;
-    let progress = client.submit_proof(&player, &receipt, &Bytes::from_slice(&env, &journal2), &1);
+    submit(&env, &client, &player, b"trial1");
+    let progress = submit(&env, &client, &player, b"trial2");
 
     assert_eq!(progress.trials_completed, 2);
     assert!(progress.is_king);
@@ -147,15 +188,7 @@ fn test_king_assignment() {
 #[test]
 fn test_round_management() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ThroneContract);
-    let client = ThroneContractClient::new(&env, &contract_id);
-
-    let admin = Address::generate(&env);
-    let image_id = BytesN::from_array(&env, &[1u8; 32]);
-
-    env.mock_all_auths();
-
-    client.initialize(&admin, &image_id, &7);
+    let (client, admin) = setup(&env, 7);
 
     let round1 = client.get_current_round();
     assert_eq!(round1, 1);
@@ -166,4 +199,684 @@ fn test_round_management() {
 
     let current = client.get_current_round();
     assert_eq!(current, 2);
+
+    // Round 1 no longer accepts proofs
+    let player = Address::generate(&env);
+    assert_eq!(
+        client.try_submit_proof(
+            &player,
+            &receipt(&env),
+            &journal(&env, b"trial1", 1, true),
+            &1
+        ),
+        Err(Ok(Error::RoundLocked))
+    );
+}
+
+// ============================================================================
+// Weighted scoring
+// ============================================================================
+
+#[test]
+fn test_trial_weights_count_towards_score() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 5);
+    let player = Address::generate(&env);
+
+    client.set_trial_weight(&admin, &trial_id(&env, b"thronebreaker"), &4);
+    assert_eq!(
+        client.get_trial_weight(&trial_id(&env, b"thronebreaker")),
+        4
+    );
+    assert_eq!(client.get_trial_weight(&trial_id(&env, b"colorSigil")), 1);
+
+    let progress = submit(&env, &client, &player, b"thronebreaker");
+    assert_eq!((progress.trials_completed, progress.score), (1, 4));
+    assert!(!progress.is_king);
+
+    let progress = submit(&env, &client, &player, b"colorSigil");
+    assert_eq!((progress.trials_completed, progress.score), (2, 5));
+    assert!(progress.is_king);
+}
+
+// ============================================================================
+// Registration
+// ============================================================================
+
+#[test]
+fn test_allowlist_registration() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 7);
+    let invited = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    let config = RoundConfig {
+        registration: Registration::Allowlist,
+        team_mode: TeamMode::Disabled,
+    };
+    client.set_round_config(&admin, &1, &config);
+    client.register_players(&admin, &1, &vec![&env, invited.clone(), invited.clone()]);
+
+    assert_eq!(
+        client.try_register(&outsider, &1, &vec![&env]),
+        Err(Ok(Error::RegistrationClosed))
+    );
+    assert_eq!(
+        client.try_submit_proof(
+            &outsider,
+            &receipt(&env),
+            &journal(&env, b"t1", 1, true),
+            &1
+        ),
+        Err(Ok(Error::NotRegistered))
+    );
+
+    submit(&env, &client, &invited, b"t1");
+    assert_eq!(
+        client.get_registered_players(&1, &0, &10),
+        vec![&env, invited]
+    );
+}
+
+#[test]
+fn test_merkle_registration() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 7);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let mallory = Address::generate(&env);
+
+    let alice_leaf = merkle::address_leaf(&env, &alice);
+    let bob_leaf = merkle::address_leaf(&env, &bob);
+    let root = pair_root(&env, &alice_leaf, &bob_leaf);
+
+    let config = RoundConfig {
+        registration: Registration::MerkleRoot(root),
+        team_mode: TeamMode::Disabled,
+    };
+    client.set_round_config(&admin, &1, &config);
+
+    // Bob's proof does not work for anyone else
+    let bob_proof = vec![&env, alice_leaf.clone()];
+    assert_eq!(
+        client.try_register(&mallory, &1, &bob_proof),
+        Err(Ok(Error::InvalidMerkleProof))
+    );
+
+    client.register(&bob, &1, &bob_proof);
+    assert!(client.is_registered(&1, &bob));
+    assert_eq!(
+        client.try_register(&bob, &1, &bob_proof),
+        Err(Ok(Error::AlreadyRegistered))
+    );
+
+    client.register(&alice, &1, &vec![&env, bob_leaf]);
+    assert_eq!(
+        client.get_registered_players(&1, &0, &10),
+        vec![&env, bob, alice.clone()]
+    );
+    assert_eq!(
+        client.get_registered_players(&1, &1, &10),
+        vec![&env, alice]
+    );
+}
+
+#[test]
+fn test_self_registration_deadline() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 7);
+    let early = Address::generate(&env);
+    let late = Address::generate(&env);
+
+    let config = RoundConfig {
+        registration: Registration::SelfRegistration(100),
+        team_mode: TeamMode::Disabled,
+    };
+    client.set_round_config(&admin, &1, &config);
+
+    env.ledger().set_timestamp(100);
+    client.register(&early, &1, &vec![&env]);
+
+    env.ledger().set_timestamp(101);
+    assert_eq!(
+        client.try_register(&late, &1, &vec![&env]),
+        Err(Ok(Error::RegistrationClosed))
+    );
+}
+
+// ============================================================================
+// Teams
+// ============================================================================
+
+fn team_round(client: &ThroneContractClient, admin: &Address, mode: TeamMode) {
+    let config = RoundConfig {
+        registration: Registration::Open,
+        team_mode: mode,
+    };
+    client.set_round_config(admin, &1, &config);
+}
+
+/// Create a team captained by the first player, with the others invited and joined
+fn form_team(client: &ThroneContractClient, team_id: u32, players: &[Address]) {
+    client.create_team(&players[0], &1, &team_id);
+    for player in &players[1..] {
+        client.invite_to_team(&players[0], &1, &team_id, player);
+        client.join_team(player, &1, &team_id);
+    }
+}
+
+#[test]
+fn test_team_mode_disabled() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env, 7);
+    let player = Address::generate(&env);
+
+    assert_eq!(
+        client.try_create_team(&player, &1, &1),
+        Err(Ok(Error::TeamModeDisabled))
+    );
+    assert_eq!(
+        client.try_join_team(&player, &1, &1),
+        Err(Ok(Error::TeamModeDisabled))
+    );
+}
+
+#[test]
+fn test_team_membership_requires_captain() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 7);
+    team_round(&client, &admin, TeamMode::UniquePerTrial);
+
+    let captain = Address::generate(&env);
+    let player = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.create_team(&captain, &1, &1);
+    assert_eq!(client.get_team_captain(&1, &1), Some(captain.clone()));
+    assert_eq!(
+        client.try_create_team(&stranger, &1, &1),
+        Err(Ok(Error::TeamExists))
+    );
+
+    // Nobody joins without an invite, and only the captain invites
+    assert_eq!(
+        client.try_join_team(&player, &1, &1),
+        Err(Ok(Error::NotInvited))
+    );
+    assert_eq!(
+        client.try_invite_to_team(&stranger, &1, &1, &player),
+        Err(Ok(Error::NotTeamCaptain))
+    );
+
+    client.invite_to_team(&captain, &1, &1, &player);
+    assert_eq!(
+        client.try_join_team(&stranger, &1, &1),
+        Err(Ok(Error::NotInvited))
+    );
+    client.join_team(&player, &1, &1);
+    assert_eq!(
+        client.get_team_members(&1, &1),
+        vec![&env, captain.clone(), player.clone()]
+    );
+
+    // The invite was used up
+    client.leave_team(&player, &1);
+    assert_eq!(client.get_team(&1, &player), None);
+    assert_eq!(
+        client.try_join_team(&player, &1, &1),
+        Err(Ok(Error::NotInvited))
+    );
+    assert_eq!(
+        client.try_leave_team(&player, &1),
+        Err(Ok(Error::NotInTeam))
+    );
+}
+
+#[test]
+fn test_team_captain_leaving() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 7);
+    team_round(&client, &admin, TeamMode::UniquePerTrial);
+
+    let players: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&env)).collect();
+    form_team(&client, 1, &players);
+
+    // Captaincy passes to the longest-standing member
+    client.leave_team(&players[0], &1);
+    assert_eq!(client.get_team_captain(&1, &1), Some(players[1].clone()));
+    assert_eq!(
+        client.get_team_members(&1, &1),
+        vec![&env, players[1].clone(), players[2].clone()]
+    );
+
+    // The old captain can start another team
+    client.create_team(&players[0], &1, &2);
+
+    // A disbanded team's ID is not reused
+    client.leave_team(&players[1], &1);
+    client.invite_to_team(&players[2], &1, &1, &players[1]);
+    client.leave_team(&players[2], &1);
+    assert_eq!(client.get_team_members(&1, &1), vec![&env]);
+    assert_eq!(
+        client.try_create_team(&players[2], &1, &1),
+        Err(Ok(Error::TeamExists))
+    );
+
+    // Nobody captains it, and earlier invites are void
+    assert_eq!(client.get_team_captain(&1, &1), None);
+    assert_eq!(
+        client.try_invite_to_team(&players[2], &1, &1, &players[0]),
+        Err(Ok(Error::NotTeamCaptain))
+    );
+    assert_eq!(
+        client.try_join_team(&players[1], &1, &1),
+        Err(Ok(Error::TeamDisbanded))
+    );
+}
+
+#[test]
+fn test_team_unique_per_trial_crowns_first_team() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    team_round(&client, &admin, TeamMode::UniquePerTrial);
+
+    let players: std::vec::Vec<Address> = (0..4).map(|_| Address::generate(&env)).collect();
+    form_team(&client, 1, &players[..2]);
+    form_team(&client, 2, &players[2..]);
+    client.invite_to_team(&players[2], &1, &2, &players[0]);
+    assert_eq!(
+        client.try_join_team(&players[0], &1, &2),
+        Err(Ok(Error::AlreadyInTeam))
+    );
+
+    // A teammate repeating a trial does not add to the team
+    submit(&env, &client, &players[0], b"t1");
+    submit(&env, &client, &players[1], b"t1");
+    assert_eq!(client.get_team_progress(&1, &1).unwrap().score, 1);
+
+    submit(&env, &client, &players[2], b"t1");
+    submit(&env, &client, &players[3], b"t2");
+    let team_two = client.get_team_progress(&1, &2).unwrap();
+    assert!(team_two.is_king);
+
+    let team_king = client.get_team_king(&1).unwrap();
+    assert_eq!(team_king.team_id, 2);
+    assert_eq!(
+        team_king.members,
+        vec![&env, players[2].clone(), players[3].clone()]
+    );
+
+    // Team one reaches the score later and does not take the crown
+    submit(&env, &client, &players[1], b"t2");
+    let team_one = client.get_team_progress(&1, &1).unwrap();
+    assert_eq!(team_one.score, 2);
+    assert!(!team_one.is_king);
+    assert_eq!(client.get_team_king(&1).unwrap().team_id, 2);
+}
+
+#[test]
+fn test_team_sum_per_member() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 3);
+    team_round(&client, &admin, TeamMode::SumPerMember);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    form_team(&client, 5, &[alice.clone(), bob.clone()]);
+
+    submit(&env, &client, &alice, b"t1");
+    submit(&env, &client, &bob, b"t1");
+    let progress = client.get_team_progress(&1, &5).unwrap();
+    assert_eq!((progress.trials_completed, progress.score), (1, 2));
+
+    submit(&env, &client, &bob, b"t2");
+    assert!(client.get_team_progress(&1, &5).unwrap().is_king);
+}
+
+// ============================================================================
+// Oracle
+// ============================================================================
+
+#[test]
+fn test_oracle_queries() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env, 1);
+    let oracle = ThroneOracleClient::new(&env, &client.address);
+    let player = Address::generate(&env);
+
+    assert_eq!(oracle.oracle_version(), throne_interface::ORACLE_VERSION);
+    assert_eq!(oracle.current_king(), None);
+
+    env.ledger().set_timestamp(50);
+    submit(&env, &client, &player, b"t1");
+
+    assert!(oracle.is_king(&1, &player));
+    assert!(oracle.has_completed(&1, &player, &trial_id(&env, b"t1")));
+    assert!(!oracle.has_completed(&1, &player, &trial_id(&env, b"t2")));
+    assert_eq!(oracle.current_king(), Some(player.clone()));
+
+    let reign = oracle.reign_info(&1).unwrap();
+    assert_eq!((reign.king, reign.crowned_at, reign.score), (player, 50, 1));
+}
+
+// ============================================================================
+// Batch settlement
+// ============================================================================
+
+#[test]
+fn test_merkle_proofs() {
+    let env = Env::default();
+    let leaves: std::vec::Vec<BytesN<32>> = (1..=4u8)
+        .map(|i| BytesN::from_array(&env, &[i; 32]))
+        .collect();
+    let left = hash_pair(&env, &leaves[0], &leaves[1]);
+    let right = hash_pair(&env, &leaves[2], &leaves[3]);
+    let root = hash_pair(&env, &left, &right);
+
+    let proof = vec![&env, leaves[3].clone(), left.clone()];
+    assert!(merkle::verify(&env, &root, &leaves[2], &proof));
+
+    // Wrong leaf, sibling or proof length
+    assert!(!merkle::verify(&env, &root, &leaves[0], &proof));
+    let wrong_sibling = vec![&env, leaves[1].clone(), left.clone()];
+    assert!(!merkle::verify(&env, &root, &leaves[2], &wrong_sibling));
+    assert!(!merkle::verify(
+        &env,
+        &root,
+        &leaves[2],
+        &vec![&env, leaves[3].clone()]
+    ));
+
+    // A single leaf is its own root
+    assert!(merkle::verify(&env, &leaves[0], &leaves[0], &vec![&env]));
+}
+
+/// Post a root over two completions of `player`; returns the operator and both leaves
+fn post_two_completions(
+    env: &Env,
+    client: &ThroneContractClient,
+    admin: &Address,
+    player: &Address,
+    second_journal: &Bytes,
+) -> (Address, CompletionLeaf, CompletionLeaf) {
+    let operator = Address::generate(env);
+    client.set_operator(admin, &operator);
+    client.set_dispute_window(admin, &100);
+
+    let first = completion_leaf(env, player, b"t1", &journal(env, b"t1", 1, true));
+    let second = completion_leaf(env, player, b"t2", second_journal);
+    let root = pair_root(env, &leaf_hash(env, &first), &leaf_hash(env, &second));
+
+    env.ledger().set_timestamp(1_000);
+    let posted = client.post_completion_root(&operator, &1, &root);
+    assert_eq!(posted.dispute_deadline, 1_100);
+    (operator, first, second)
+}
+
+#[test]
+fn test_completion_root_dispute_window() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    let player = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let root = BytesN::from_array(&env, &[9u8; 32]);
+
+    assert_eq!(
+        client.try_post_completion_root(&stranger, &1, &root),
+        Err(Ok(Error::UnauthorizedOperator))
+    );
+
+    let (operator, first, second) = post_two_completions(
+        &env,
+        &client,
+        &admin,
+        &player,
+        &journal(&env, b"t2", 1, true),
+    );
+    assert_eq!(
+        client.try_post_completion_root(&operator, &1, &root),
+        Err(Ok(Error::RootAlreadyPosted))
+    );
+
+    let leaves = vec![&env, first.clone(), second.clone()];
+    let proofs = vec![
+        &env,
+        vec![&env, leaf_hash(&env, &second)],
+        vec![&env, leaf_hash(&env, &first)],
+    ];
+
+    // Claims open only after the deadline has passed
+    env.ledger().set_timestamp(1_100);
+    assert_eq!(
+        client.try_claim_completions(&1, &leaves, &proofs),
+        Err(Ok(Error::DisputeWindowOpen))
+    );
+
+    env.ledger().set_timestamp(1_101);
+    assert_eq!(client.claim_completions(&1, &leaves, &proofs), 2);
+    assert_eq!(client.get_king(&1), Some(player.clone()));
+
+    // Already applied leaves are skipped
+    assert_eq!(client.claim_completions(&1, &leaves, &proofs), 0);
+
+    // Proofs must match the root
+    let swapped = vec![&env, vec![&env, leaf_hash(&env, &first)], vec![&env]];
+    assert_eq!(
+        client.try_claim_completions(&1, &leaves, &swapped),
+        Err(Ok(Error::InvalidMerkleProof))
+    );
+}
+
+#[test]
+fn test_challenge_root() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    let player = Address::generate(&env);
+    let challenger = Address::generate(&env);
+
+    // The second leaf commits to a journal of a failed trial
+    let bad_journal = journal(&env, b"t2", 1, false);
+    let (operator, first, second) =
+        post_two_completions(&env, &client, &admin, &player, &bad_journal);
+    let first_proof = vec![&env, leaf_hash(&env, &second)];
+    let second_proof = vec![&env, leaf_hash(&env, &first)];
+
+    // Valid leaves cannot be challenged
+    let good_journal = journal(&env, b"t1", 1, true);
+    assert_eq!(
+        client.try_challenge_root(
+            &challenger,
+            &1,
+            &first,
+            &first_proof,
+            &receipt(&env),
+            &good_journal
+        ),
+        Err(Ok(Error::ChallengeRejected))
+    );
+
+    // Nor with a receipt other than the committed one
+    assert_eq!(
+        client.try_challenge_root(
+            &challenger,
+            &1,
+            &first,
+            &first_proof,
+            &Bytes::new(&env),
+            &good_journal
+        ),
+        Err(Ok(Error::ChallengeRejected))
+    );
+
+    // The revealed journal must be the committed one
+    assert_eq!(
+        client.try_challenge_root(
+            &challenger,
+            &1,
+            &second,
+            &second_proof,
+            &receipt(&env),
+            &good_journal
+        ),
+        Err(Ok(Error::ChallengeRejected))
+    );
+
+    client.challenge_root(
+        &challenger,
+        &1,
+        &second,
+        &second_proof,
+        &receipt(&env),
+        &bad_journal,
+    );
+    assert!(client.get_completion_root(&1).unwrap().challenged);
+
+    env.ledger().set_timestamp(2_000);
+    let leaves = vec![&env, first.clone()];
+    assert_eq!(
+        client.try_claim_completions(&1, &leaves, &vec![&env, first_proof]),
+        Err(Ok(Error::RootChallenged))
+    );
+
+    // A challenged root can be replaced
+    let reposted = client.post_completion_root(&operator, &1, &leaf_hash(&env, &first));
+    assert!(!reposted.challenged);
+
+    // No challenges once the window has closed
+    env.ledger().set_timestamp(reposted.dispute_deadline + 1);
+    assert_eq!(
+        client.try_challenge_root(
+            &challenger,
+            &1,
+            &first,
+            &vec![&env],
+            &receipt(&env),
+            &good_journal
+        ),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+    assert_eq!(
+        client.claim_completions(&1, &leaves, &vec![&env, vec![&env]]),
+        1
+    );
+}
+
+#[test]
+fn test_claim_completions_after_round_locked() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    let player = Address::generate(&env);
+
+    let (_operator, first, second) = post_two_completions(
+        &env,
+        &client,
+        &admin,
+        &player,
+        &journal(&env, b"t2", 1, true),
+    );
+    client.start_new_round(&admin);
+
+    env.ledger().set_timestamp(2_000);
+    let leaves = vec![&env, first.clone()];
+    let proofs = vec![&env, vec![&env, leaf_hash(&env, &second)]];
+    assert_eq!(
+        client.try_claim_completions(&1, &leaves, &proofs),
+        Err(Ok(Error::RoundLocked))
+    );
+    assert_eq!(client.get_progress(&1, &player), None);
+}
+
+#[test]
+fn test_leaf_dispute_answered() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    let player = Address::generate(&env);
+    let challenger = Address::generate(&env);
+
+    let second_journal = journal(&env, b"t2", 1, true);
+    let (_operator, first, second) =
+        post_two_completions(&env, &client, &admin, &player, &second_journal);
+    let second_proof = vec![&env, leaf_hash(&env, &first)];
+
+    let dispute = client.dispute_leaf(&challenger, &1, &second, &second_proof);
+    assert_eq!(dispute.respond_by, 1_100);
+    assert_eq!(client.get_completion_root(&1).unwrap().open_disputes, 1);
+    assert_eq!(
+        client.try_dispute_leaf(&challenger, &1, &second, &second_proof),
+        Err(Ok(Error::DisputeOpen))
+    );
+
+    // Claims wait for the answer, even after the dispute window
+    let leaves = vec![&env, first.clone(), second.clone()];
+    let proofs = vec![
+        &env,
+        vec![&env, leaf_hash(&env, &second)],
+        second_proof.clone(),
+    ];
+    env.ledger().set_timestamp(1_050);
+    assert_eq!(
+        client.try_claim_completions(&1, &leaves, &proofs),
+        Err(Ok(Error::DisputeWindowOpen))
+    );
+    env.ledger().set_timestamp(1_100);
+    assert_eq!(
+        client.try_resolve_dispute(&1, &leaf_hash(&env, &second)),
+        Err(Ok(Error::ResponseWindowOpen))
+    );
+
+    // Only the committed receipt and journal answer the dispute
+    assert_eq!(
+        client.try_respond_to_dispute(&1, &second, &Bytes::new(&env), &second_journal),
+        Err(Ok(Error::InvalidProof))
+    );
+    client.respond_to_dispute(&1, &second, &receipt(&env), &second_journal);
+    assert_eq!(client.get_completion_root(&1).unwrap().open_disputes, 0);
+    assert_eq!(
+        client.try_respond_to_dispute(&1, &second, &receipt(&env), &second_journal),
+        Err(Ok(Error::DisputeNotFound))
+    );
+
+    env.ledger().set_timestamp(1_101);
+    assert_eq!(client.claim_completions(&1, &leaves, &proofs), 2);
+}
+
+#[test]
+fn test_leaf_dispute_unanswered() {
+    let env = Env::default();
+    let (client, admin) = setup(&env, 2);
+    let player = Address::generate(&env);
+    let challenger = Address::generate(&env);
+
+    let (_operator, first, second) = post_two_completions(
+        &env,
+        &client,
+        &admin,
+        &player,
+        &journal(&env, b"t2", 1, true),
+    );
+    let first_proof = vec![&env, leaf_hash(&env, &second)];
+
+    // Disputed just before the window closes
+    env.ledger().set_timestamp(1_100);
+    client.dispute_leaf(&challenger, &1, &first, &first_proof);
+
+    env.ledger().set_timestamp(1_150);
+    assert_eq!(
+        client.try_claim_completions(&1, &vec![&env, first.clone()], &vec![&env, first_proof]),
+        Err(Ok(Error::DisputeOpen))
+    );
+
+    env.ledger().set_timestamp(1_201);
+    assert_eq!(
+        client.try_respond_to_dispute(&1, &first, &receipt(&env), &journal(&env, b"t1", 1, true)),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+    client.resolve_dispute(&1, &leaf_hash(&env, &first));
+    assert!(client.get_completion_root(&1).unwrap().challenged);
+
+    // No new disputes against an invalidated root
+    assert_eq!(
+        client.try_dispute_leaf(&challenger, &1, &second, &vec![&env]),
+        Err(Ok(Error::RootChallenged))
+    );
 }
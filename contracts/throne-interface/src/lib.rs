@@ -0,0 +1,58 @@
+#![no_std]
+
+//! # Throne Oracle Interface
+//!
+//! Stable queries other contracts can use to gate features on Throne state,
+//! e.g. "is this address the King of round N" or "did this address complete
+//! trial T".
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! use throne_interface::ThroneOracleClient;
+//!
+//! let throne = ThroneOracleClient::new(&env, &throne_address);
+//! if throne.is_king(&round_id, &player) {
+//!     // unlock the crown cosmetic
+//! }
+//! ```
+//!
+//! ## Versioning
+//!
+//! The interface is versioned by `ORACLE_VERSION`. Existing methods keep
+//! their signatures within a version; callers can check `oracle_version`
+//! before relying on methods added later.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env};
+
+/// Current version of the oracle interface
+pub const ORACLE_VERSION: u32 = 1;
+
+/// A King's reign over a round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReignInfo {
+    pub round_id: u32,
+    pub king: Address,
+    pub crowned_at: u64,
+    pub score: u32,
+}
+
+/// Read-only Throne queries for partner contracts
+#[contractclient(name = "ThroneOracleClient")]
+pub trait ThroneOracle {
+    /// Version of this interface implemented by the contract
+    fn oracle_version(env: Env) -> u32;
+
+    /// Check if `player` is the King of `round_id`
+    fn is_king(env: Env, round_id: u32, player: Address) -> bool;
+
+    /// Check if `player` completed `trial_id` in `round_id`
+    fn has_completed(env: Env, round_id: u32, player: Address, trial_id: BytesN<32>) -> bool;
+
+    /// King of the current round, if crowned
+    fn current_king(env: Env) -> Option<Address>;
+
+    /// Reign details for a round, if a King was crowned
+    fn reign_info(env: Env, round_id: u32) -> Option<ReignInfo>;
+}
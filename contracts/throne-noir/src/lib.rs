@@ -1,9 +1,11 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, contractclient, panic_with_error, symbol_short, Address,
-    Bytes, BytesN, Env, Vec,
+    contract, contracterror, contractimpl, contracttype, contractclient, symbol_short, token,
+    Address, Bytes, BytesN, Env, Map, String, Vec,
 };
+use core::cmp::Reverse;
+use throne_attestation::{FailureAttestation, TrialAttestation};
 
 // ============================================================================
 // GAME HUB INTEGRATION (Required for Stellar Hackathon)
@@ -33,21 +35,165 @@ pub trait GameHub {
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
+    Admin,
+    PendingAdmin,      // Proposed admin awaiting acceptance
     RoundId,
     RequiredTrials,
-    King,
+    King(u32),         // King of each round (kept for past rounds)
     IsLocked,
-    BackendPubKey,
+    BackendKey(u32),   // key_id -> BackendKey
+    BackendKeyCount,   // Number of backend keys ever added (next key_id)
+    AttestationThreshold, // Backend signatures required per attestation (M of N)
     GameHub,           // Game Hub contract address
-    SessionId,         // Current multiplayer session ID
-    SessionPlayer1,    // Player 1 in current session 
-    SessionPlayer2,    // Player 2 in current session 
-    SessionAllPlayers, // All players (2-4) in current session
-    SessionStarted,    // Has start_game been called for current session?
-    Progress(Address),
-    Nonce(Address),
+    Session(u32),      // session_id -> Session
+    SessionProgress(u32, Address), // (session_id, player) -> completed trials
+    SessionTrials(u32, Address), // (session_id, player) -> bitmap of completed trial IDs
+    SessionLastTrial(u32, Address), // (session_id, player) -> timestamp of latest trial
+    SessionDuration,   // Seconds a started session runs before it can be expired
+    SessionSeed(u32),  // session_id -> question variant seed derived at start
+    PendingHubReport(u32), // session_id -> Game Hub calls that failed
+    DefaultPoints,     // Points staked by players who declare none
+    WagerConfig,       // Token and admin fee of session wagers
+    LockoutConfig,     // How long failed trials lock players out
+    Failures(Address), // Player -> FailureRecord
+    PlayerSession(Address), // Session a player has joined and not yet finished
+    RoundProgress(u32, Address), // (round_id, player) -> completed trials
+    RoundTrials(u32, Address), // (round_id, player) -> bitmap of completed trial IDs
+    Nonce(Address),       // Highest nonce used by a player
+    NonceWindow(Address), // Bitmap of used nonces below the highest (bit i = highest - i)
 }
 
+/// Backend attestation signing key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackendKey {
+    pub pubkey: BytesN<32>,
+    pub active_from: u32,         // First ledger the key is valid
+    pub expires_at: Option<u32>,  // First ledger the key is no longer valid
+    pub revoked: bool,
+}
+
+/// Lifecycle of a multiplayer session, mirroring the backend room states
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SessionStatus {
+    Waiting,   // Lobby open: players join, leave and mark themselves ready
+    Countdown, // Started by the host: roster locked and reported to the Game Hub
+    Finished,  // Won by completing all trials, by forfeits or on expiry
+    Abandoned, // Expired with no trial completed: no winner, stakes refunded
+}
+
+/// Multiplayer session
+/// Sessions race independently of each other and of the game round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Session {
+    pub session_id: u32,
+    pub host: Address,
+    pub players: Vec<Address>, // Joined players in join order (host first)
+    pub ready: Vec<Address>,   // Joined players that are ready to start
+    pub status: SessionStatus,
+    pub winner: Option<Address>,
+    pub deadline: Option<u64>,    // Ledger timestamp the session ends, set on start
+    pub forfeited: Vec<Address>,  // Players who gave up after the start
+    pub hub_pair: Vec<Address>,   // [player1, player2] reported to the Game Hub, once known
+    pub points: Map<Address, i128>, // Declared point stakes; every player's once started
+    pub stake: i128,                // Wager each player escrows on joining (0 = none)
+    pub wager_token: Option<Address>, // Token the stakes are paid in
+    pub fee_bps: u32,               // Admin fee on the pot, fixed when the session is created
+    pub pot: i128,                  // Stakes currently held in escrow
+    pub seed_commits: Map<Address, BytesN<32>>, // SHA256 of each player's seed secret
+    pub seed_reveals: Map<Address, BytesN<32>>, // Seed secrets revealed so far
+    pub reveal_by: Option<u64>, // Unrevealed players can be dropped after this; set on first reveal
+}
+
+/// Token session wagers are paid in, and the admin's cut of each pot
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WagerConfig {
+    pub token: Address,
+    pub fee_bps: u32, // Basis points of the pot kept as admin fee
+}
+
+/// How long a failed trial locks a player out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockoutConfig {
+    pub duration: u64,     // Seconds locked out after a failure
+    pub escalating: bool,  // Double the lockout with each further failure in a round
+    pub max_duration: u64, // Cap on escalated lockouts
+}
+
+/// A player's failed trials
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailureRecord {
+    pub round_id: u32,     // Game round the failures count towards
+    pub failures: u32,     // Failures in that round
+    pub last_failed_at: u64,
+    pub locked_until: u64, // Ledger timestamp the lockout ends
+}
+
+/// A participant's standing in a session
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionResult {
+    pub player: Address,
+    pub progress: u32,              // Completed trials
+    pub last_trial_at: Option<u64>, // Ledger timestamp of the latest completed trial
+    pub finished_at: Option<u64>,   // Ledger timestamp of the last required trial
+    pub forfeited: bool,
+    pub points: i128,               // Points staked in the session
+}
+
+/// Game Hub calls of a session that failed and await `retry_hub_report`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingHubReport {
+    pub start_pending: bool,       // start_game not delivered yet
+    pub player1_won: Option<bool>, // end_game result not delivered yet
+}
+
+// ============================================================================
+// STORAGE TTL
+// ============================================================================
+
+// Per-player and per-session entries live in persistent storage
+const LEDGER_TTL_THRESHOLD: u32 = 518_400; // ~30 days
+const LEDGER_TTL_EXTENSION: u32 = 518_400;
+
+// Contract config lives in instance storage
+const INSTANCE_TTL_THRESHOLD: u32 = 17_280; // ~1 day
+const INSTANCE_TTL_EXTENSION: u32 = 518_400;
+
+// ============================================================================
+// TRIALS
+// ============================================================================
+
+// Completed trials are kept as a u64 bitmap (bit n-1 set = trial n done)
+const MAX_TRIALS: u32 = 64;
+const DEFAULT_LOCKOUT_DURATION: u64 = 120; // 2 minutes
+
+// Nonces up to this far below a player's highest nonce may still be used once
+const NONCE_WINDOW: u64 = 64;
+
+// ============================================================================
+// ATTESTATION
+// ============================================================================
+
+// Longest strkey an Address can render to (muxed account)
+const STRKEY_MAX_LEN: usize = 69;
+
+// ============================================================================
+// SESSIONS
+// ============================================================================
+
+const MIN_SESSION_PLAYERS: u32 = 2;
+const MAX_SESSION_PLAYERS: u32 = 4;
+const DEFAULT_SESSION_DURATION: u64 = 3_600; // 1 hour
+const SEED_REVEAL_WINDOW: u64 = 300; // 5 minutes
+const MAX_FEE_BPS: u32 = 10_000;
+
 // ============================================================================
 // ERROR CODES
 // ============================================================================
@@ -60,6 +206,46 @@ pub enum Error {
     InvalidNonce = 2,
     InvalidSignature = 3,
     AlreadyKing = 4,
+    AlreadyInitialized = 5,
+    NotAdmin = 6,
+    NoPendingAdmin = 7,
+    InvalidPlayerCount = 8,
+    NotInitialized = 9,
+    SessionNotFound = 10,
+    NotSessionPlayer = 11,
+    UnknownBackendKey = 12,
+    InactiveBackendKey = 13,
+    InsufficientSignatures = 14,
+    DuplicateSigner = 15,
+    InvalidThreshold = 16,
+    AttestationExpired = 17,
+    SessionExists = 18,
+    SessionNotWaiting = 19,
+    SessionFull = 20,
+    AlreadyJoined = 21,
+    NotSessionHost = 22,
+    PlayersNotReady = 23,
+    SessionNotStarted = 24,
+    AlreadyFinished = 25,
+    SessionExpired = 26,
+    SessionNotExpired = 27,
+    SessionOver = 28,
+    AlreadyForfeited = 29,
+    NoPendingHubReport = 30,
+    HubUnavailable = 31,
+    InvalidPoints = 32,
+    InvalidStake = 33,
+    WagersNotConfigured = 34,
+    InvalidFee = 35,
+    InvalidTrial = 36,
+    TrialAlreadyCompleted = 37,
+    LockedOut = 38,
+    InvalidLockout = 39,
+    BelowThreshold = 40,
+    SeedRevealStarted = 41,
+    InvalidSeedReveal = 42,
+    SeedsNotRevealed = 43,
+    RevealWindowOpen = 44,
 }
 
 // ============================================================================
@@ -83,13 +269,21 @@ impl Throne {
         backend_pubkey: BytesN<32>,
         required_trials: u32,
         game_hub: Address,  // Game Hub contract address (required for hackathon)
-    ) {
+    ) -> Result<(), Error> {
         admin.require_auth();
 
-        // Store backend public key for signature verification
-        env.storage()
-            .instance()
-            .set(&DataKey::BackendPubKey, &backend_pubkey);
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        if required_trials == 0 || required_trials > MAX_TRIALS {
+            return Err(Error::InvalidTrial);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+
+        // Store backend public key for signature verification (key_id 0)
+        Self::store_backend_key(&env, &backend_pubkey, env.ledger().sequence(), None);
 
         // Store Game Hub address for lifecycle reporting
         env.storage()
@@ -107,75 +301,458 @@ impl Throne {
             (symbol_short!("init"),),
             (admin, backend_pubkey, required_trials, game_hub),
         );
+
+        Ok(())
     }
 
     // ========================================================================
     // MULTIPLAYER SESSION (Game Hub Integration)
     // ========================================================================
 
-    /// Start a multiplayer session with 2-4 players
-    /// REQUIRED: Calls Game Hub's start_game() for hackathon compliance
-    /// Frontend/backend should call this when a multiplayer room countdown finishes
-    /// 
-    /// NOTE: Game Hub only supports 2-player tracking, so only first 2 players
-    /// are reported to Game Hub. All players can still play and compete.
-    /// 
-    /// # Arguments
-    /// * `session_id` - Unique session identifier
-    /// * `players` - Vec of 2-4 player addresses
-    pub fn start_multiplayer_session(
+    /// Open a session lobby hosted by `host`
+    /// Mirrors the backend room service: the host joins and is ready at once.
+    /// Any number of sessions can be open; a player takes part in one at a time.
+    ///
+    /// With a non-zero `stake`, every player escrows that amount of the
+    /// configured wager token on joining; the winner takes the pot minus the
+    /// admin fee.
+    ///
+    /// `seed_commit` is SHA256 of a secret the host reveals with
+    /// `reveal_seed` before the start; see `start_session`.
+    pub fn create_session(
         env: Env,
+        host: Address,
         session_id: u32,
-        players: Vec<Address>,
-    ) {
-        // Validate player count (2-4 players)
-        let player_count = players.len();
-        if player_count < 2 || player_count > 4 {
-            panic_with_error!(&env, Error::InvalidNonce); // Reuse error code
+        stake: i128,
+        seed_commit: BytesN<32>,
+    ) -> Result<(), Error> {
+        host.require_auth();
+
+        let session_key = DataKey::Session(session_id);
+        if env.storage().persistent().has(&session_key) {
+            return Err(Error::SessionExists);
+        }
+        if stake < 0 {
+            return Err(Error::InvalidStake);
         }
+        let (wager_token, fee_bps) = if stake > 0 {
+            let config = Self::get_wager_config(env.clone()).ok_or(Error::WagersNotConfigured)?;
+            (Some(config.token), config.fee_bps)
+        } else {
+            (None, 0)
+        };
+        Self::enter_session(&env, &host, session_id)?;
+
+        let mut session = Session {
+            session_id,
+            host: host.clone(),
+            players: Vec::from_array(&env, [host.clone()]),
+            ready: Vec::from_array(&env, [host.clone()]),
+            status: SessionStatus::Waiting,
+            winner: None,
+            deadline: None,
+            forfeited: Vec::new(&env),
+            hub_pair: Vec::new(&env),
+            points: Map::new(&env),
+            stake,
+            wager_token,
+            fee_bps,
+            pot: 0,
+            seed_commits: Map::from_array(&env, [(host.clone(), seed_commit)]),
+            seed_reveals: Map::new(&env),
+            reveal_by: None,
+        };
+        Self::escrow_stake(&env, &mut session, &host);
+        Self::save_session(&env, &session);
+        Self::extend_instance_ttl(&env);
 
-        // Require auth from first player (host)
-        let player1 = players.get(0).expect("Player 1 required");
-        player1.require_auth();
+        env.events()
+            .publish((symbol_short!("lobby"),), (session_id, host, stake));
 
-        // Get player 2
-        let player2 = players.get(1).expect("Player 2 required");
+        Ok(())
+    }
 
-        // Get Game Hub address
-        let game_hub_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::GameHub)
-            .expect("Game Hub not configured");
-
-        // Store ALL players in session
-        env.storage().instance().set(&DataKey::SessionAllPlayers, &players);
-        
-        // Store first 2 players for Game Hub compatibility
-        env.storage().instance().set(&DataKey::SessionId, &session_id);
-        env.storage().instance().set(&DataKey::SessionPlayer1, &player1);
-        env.storage().instance().set(&DataKey::SessionPlayer2, &player2);
-        env.storage().instance().set(&DataKey::SessionStarted, &true);
-
-        // HACKATHON REQUIREMENT: Call Game Hub's start_game()
-        // This reports the game start to the ecosystem
-        // NOTE: Only first 2 players reported (Game Hub limitation)
-        let game_hub_client = GameHubClient::new(&env, &game_hub_addr);
-        let game_id = env.current_contract_address();
-        
-        game_hub_client.start_game(
-            &game_id,
-            &session_id,
-            &player1,
-            &player2,
-            &0i128,  // Initial points (both start at 0)
-            &0i128,
+    /// Join a waiting session (2-4 players)
+    /// Each player signs their own join; joining does not mark them ready.
+    /// `seed_commit` is SHA256 of the player's seed secret.
+    pub fn join_session(
+        env: Env,
+        player: Address,
+        session_id: u32,
+        seed_commit: BytesN<32>,
+    ) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::open_lobby(&env, session_id)?;
+        if session.players.len() >= MAX_SESSION_PLAYERS {
+            return Err(Error::SessionFull);
+        }
+        Self::enter_session(&env, &player, session_id)?;
+
+        session.players.push_back(player.clone());
+        session.seed_commits.set(player.clone(), seed_commit);
+        Self::escrow_stake(&env, &mut session, &player);
+        Self::save_session(&env, &session);
+
+        env.events().publish(
+            (symbol_short!("join"),),
+            (session_id, player, session.players.len()),
+        );
+
+        Ok(())
+    }
+
+    /// Leave a waiting session, refunding the player's stake
+    /// If the host leaves, the next player to have joined becomes host; the
+    /// lobby is closed when the last player leaves
+    pub fn leave_session(env: Env, player: Address, session_id: u32) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::open_lobby(&env, session_id)?;
+        let index = session
+            .players
+            .first_index_of(&player)
+            .ok_or(Error::NotSessionPlayer)?;
+        session.players.remove(index);
+        if let Some(ready_index) = session.ready.first_index_of(&player) {
+            session.ready.remove(ready_index);
+        }
+        session.points.remove(player.clone());
+        session.seed_commits.remove(player.clone());
+        let stake = session.stake;
+        Self::release_stake(&env, &mut session, &player, stake);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PlayerSession(player.clone()));
+
+        match session.players.first() {
+            None => env
+                .storage()
+                .persistent()
+                .remove(&DataKey::Session(session_id)),
+            Some(next_host) => {
+                if session.host == player {
+                    session.host = next_host;
+                }
+                Self::save_session(&env, &session);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("leave"),),
+            (session_id, player, session.players.len()),
         );
 
+        Ok(())
+    }
+
+    /// Mark a joined player as ready (or not) to start a waiting session
+    pub fn set_ready(
+        env: Env,
+        player: Address,
+        session_id: u32,
+        ready: bool,
+    ) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::open_lobby(&env, session_id)?;
+        if !session.players.contains(&player) {
+            return Err(Error::NotSessionPlayer);
+        }
+
+        let ready_index = session.ready.first_index_of(&player);
+        match (ready, ready_index) {
+            (true, None) => session.ready.push_back(player.clone()),
+            (false, Some(index)) => {
+                session.ready.remove(index);
+            }
+            _ => {}
+        }
+        Self::save_session(&env, &session);
+
+        env.events()
+            .publish((symbol_short!("ready"),), (session_id, player, ready));
+
+        Ok(())
+    }
+
+    /// Declare the points a joined player stakes in a waiting session
+    /// Players who declare none stake the admin-set default
+    pub fn declare_points(
+        env: Env,
+        player: Address,
+        session_id: u32,
+        points: i128,
+    ) -> Result<(), Error> {
+        player.require_auth();
+
+        if points < 0 {
+            return Err(Error::InvalidPoints);
+        }
+
+        let mut session = Self::waiting_session(&env, session_id)?;
+        if !session.players.contains(&player) {
+            return Err(Error::NotSessionPlayer);
+        }
+
+        session.points.set(player.clone(), points);
+        Self::save_session(&env, &session);
+
+        env.events()
+            .publish((symbol_short!("points"),), (session_id, player, points));
+
+        Ok(())
+    }
+
+    /// Reveal the secret behind a player's seed commitment
+    /// Opens once 2-4 players joined and all are ready. The first reveal locks
+    /// the lobby (no more joins, leaves or ready changes) and starts the
+    /// reveal window; see `drop_unrevealed`.
+    pub fn reveal_seed(
+        env: Env,
+        player: Address,
+        session_id: u32,
+        secret: BytesN<32>,
+    ) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::waiting_session(&env, session_id)?;
+        let commit = session
+            .seed_commits
+            .get(player.clone())
+            .ok_or(Error::NotSessionPlayer)?;
+        let player_count = session.players.len();
+        if !(MIN_SESSION_PLAYERS..=MAX_SESSION_PLAYERS).contains(&player_count) {
+            return Err(Error::InvalidPlayerCount);
+        }
+        if session.ready.len() != player_count {
+            return Err(Error::PlayersNotReady);
+        }
+        let secret_hash: BytesN<32> = env.crypto().sha256(&secret.clone().into()).into();
+        if session.seed_reveals.contains_key(player.clone()) || secret_hash != commit {
+            return Err(Error::InvalidSeedReveal);
+        }
+
+        session.seed_reveals.set(player.clone(), secret);
+        if session.reveal_by.is_none() {
+            session.reveal_by = Some(env.ledger().timestamp() + SEED_REVEAL_WINDOW);
+        }
+        Self::save_session(&env, &session);
+
+        env.events()
+            .publish((symbol_short!("reveal"),), (session_id, player));
+
+        Ok(())
+    }
+
+    /// Drop the players who did not reveal their seed secret in time
+    /// Anyone can call this once the reveal window is over. Dropped players
+    /// lose their stake to the pot. If fewer than 2 players remain, the
+    /// session is abandoned and the remaining player takes back the pot.
+    pub fn drop_unrevealed(env: Env, session_id: u32) -> Result<Vec<Address>, Error> {
+        let mut session = Self::waiting_session(&env, session_id)?;
+        if !Self::reveal_window_over(&env, &session) {
+            return Err(Error::RevealWindowOpen);
+        }
+
+        let mut dropped = Vec::new(&env);
+        for player in session.players.clone().iter() {
+            if session.seed_reveals.contains_key(player.clone()) {
+                continue;
+            }
+            let index = session.players.first_index_of(&player).unwrap();
+            session.players.remove(index);
+            if let Some(ready_index) = session.ready.first_index_of(&player) {
+                session.ready.remove(ready_index);
+            }
+            session.points.remove(player.clone());
+            session.seed_commits.remove(player.clone());
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PlayerSession(player.clone()));
+            dropped.push_back(player);
+        }
+
+        if session.players.len() < MIN_SESSION_PLAYERS {
+            // Only revealers remain, so the reveal window guarantees one
+            let remaining = session.players.first_unchecked();
+            let forfeited_stakes = session.pot - session.stake;
+            Self::release_stake(&env, &mut session, &remaining, forfeited_stakes);
+            Self::abandon_session(&env, session);
+        } else {
+            if !session.players.contains(&session.host) {
+                session.host = session.players.first_unchecked();
+            }
+            Self::save_session(&env, &session);
+        }
+
+        env.events()
+            .publish((symbol_short!("dropped"),), (session_id, dropped.clone()));
+
+        Ok(dropped)
+    }
+
+    /// Start a waiting session once 2-4 players joined, all are ready and all
+    /// revealed their seed secrets (host only, or any player once the reveal
+    /// window is over so a host who stalls can't lock the stakes). Moves the
+    /// session to COUNTDOWN, starts its deadline and derives its question
+    /// variant seed.
+    /// Seed = SHA256(session_id as u32 BE || revealed secrets in join order),
+    /// so no single player, the host included, can choose it.
+    ///
+    /// The Game Hub hears about the session once it has a winner: start_game()
+    /// and end_game() are then sent together, so the hub never holds a game
+    /// that can't end (sessions abandoned without progress are not reported).
+    pub fn start_session(env: Env, player: Address, session_id: u32) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::waiting_session(&env, session_id)?;
+        if session.host != player {
+            if !session.players.contains(&player) {
+                return Err(Error::NotSessionPlayer);
+            }
+            if !Self::reveal_window_over(&env, &session) {
+                return Err(Error::NotSessionHost);
+            }
+        }
+
+        let player_count = session.players.len();
+        if !(MIN_SESSION_PLAYERS..=MAX_SESSION_PLAYERS).contains(&player_count) {
+            return Err(Error::InvalidPlayerCount);
+        }
+        if session.ready.len() != player_count {
+            return Err(Error::PlayersNotReady);
+        }
+        if session.seed_reveals.len() != player_count {
+            return Err(Error::SeedsNotRevealed);
+        }
+
+        let mut preimage = Bytes::from_array(&env, &session_id.to_be_bytes());
+        for player in session.players.iter() {
+            let secret = session.seed_reveals.get_unchecked(player);
+            preimage.extend_from_array(&secret.to_array());
+        }
+        let seed: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        session.status = SessionStatus::Countdown;
+        session.deadline = Some(env.ledger().timestamp() + Self::get_session_duration(env.clone()));
+        let default_points = Self::get_default_points(env.clone());
+        for player in session.players.iter() {
+            if !session.points.contains_key(player.clone()) {
+                session.points.set(player, default_points);
+            }
+        }
+        Self::save_session(&env, &session);
+        Self::extend_instance_ttl(&env);
+
+        let seed_key = DataKey::SessionSeed(session_id);
+        env.storage().persistent().set(&seed_key, &seed);
+        Self::extend_persistent_ttl(&env, &seed_key);
+
         env.events().publish(
             (symbol_short!("session"),),
-            (session_id, player_count),
+            (session_id, player_count, seed),
         );
+
+        Ok(())
+    }
+
+    /// Give up a started session
+    /// The last player who has not forfeited wins the session.
+    pub fn forfeit(env: Env, session_id: u32, player: Address) -> Result<(), Error> {
+        player.require_auth();
+
+        let mut session = Self::running_session(&env, session_id)?;
+        if !session.players.contains(&player) {
+            return Err(Error::NotSessionPlayer);
+        }
+        if session.forfeited.contains(&player) {
+            return Err(Error::AlreadyForfeited);
+        }
+
+        session.forfeited.push_back(player.clone());
+        Self::save_session(&env, &session);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PlayerSession(player.clone()));
+
+        env.events()
+            .publish((symbol_short!("forfeit"),), (session_id, player));
+
+        let mut remaining = session
+            .players
+            .iter()
+            .filter(|session_player| !session.forfeited.contains(session_player));
+        if let (Some(last_player), None) = (remaining.next(), remaining.next()) {
+            Self::finish_session(&env, session, &last_player)?;
+        }
+
+        Ok(())
+    }
+
+    /// Settle a started session whose deadline has passed (anyone can call)
+    /// The player with the most completed trials wins; ties go to whoever
+    /// completed their latest trial first. Players who forfeited cannot win.
+    ///
+    /// If nobody completed a trial the session is abandoned instead: there
+    /// is no winner, every stake is refunded and no result is reported to
+    /// the Game Hub, which has no way to record a draw.
+    ///
+    /// # Returns
+    /// * The winner, or None if the session was abandoned
+    pub fn expire_session(env: Env, session_id: u32) -> Result<Option<Address>, Error> {
+        let session = Self::running_session(&env, session_id)?;
+        let deadline = session.deadline.ok_or(Error::SessionNotStarted)?;
+        if env.ledger().timestamp() <= deadline {
+            return Err(Error::SessionNotExpired);
+        }
+
+        let no_progress = session
+            .players
+            .iter()
+            .all(|player| Self::get_session_progress(env.clone(), session_id, player) == 0);
+        if no_progress {
+            Self::abandon_session(&env, session);
+            return Ok(None);
+        }
+
+        // Forfeits hand the session over before everyone has left
+        let winner = Self::best_player(&env, &session, None).ok_or(Error::InvalidPlayerCount)?;
+
+        env.events()
+            .publish((symbol_short!("expired"),), (session_id, winner.clone()));
+
+        Self::finish_session(&env, session, &winner)?;
+
+        Ok(Some(winner))
+    }
+
+    /// Re-deliver Game Hub calls of a session that failed (anyone can call)
+    /// Fails with `HubUnavailable` while the hub keeps rejecting them.
+    pub fn retry_hub_report(env: Env, session_id: u32) -> Result<(), Error> {
+        let key = DataKey::PendingHubReport(session_id);
+        let pending: PendingHubReport = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoPendingHubReport)?;
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+
+        if pending.start_pending && !Self::hub_start_game(&env, &session)? {
+            return Err(Error::HubUnavailable);
+        }
+        if let Some(player1_won) = pending.player1_won {
+            if !Self::hub_end_game(&env, session_id, player1_won)? {
+                return Err(Error::HubUnavailable);
+            }
+        }
+
+        env.storage().persistent().remove(&key);
+
+        env.events()
+            .publish((symbol_short!("hubsent"),), (session_id,));
+
+        Ok(())
     }
 
     // ========================================================================
@@ -186,258 +763,411 @@ impl Throne {
     /// Backend has already verified the ZK proof off-chain
     /// This function verifies the backend's signature and updates progress
     /// Each trial has its own roundId (1, 2, 3...) for unique proof verification
+    ///
+    /// With a `session_id`, the trial counts towards that session and only its
+    /// players may submit; they can keep playing after the session is won to
+    /// record their own finish. Without one, the player races for the King of
+    /// the current game round.
+    ///
+    /// `signatures` holds `(key_id, signature)` pairs from currently valid
+    /// backend keys; at least the attestation threshold of distinct keys
+    /// must sign the same message.
+    ///
+    /// Players locked out by a failed trial are rejected until the lockout
+    /// ends; see `record_failure`.
+    #[allow(clippy::too_many_arguments)]
     pub fn submit_proof(
         env: Env,
         player: Address,
         solution_hash: BytesN<32>,
-        signature: BytesN<64>,
+        signatures: Vec<(u32, BytesN<64>)>,
         nonce: u64,
         trial_round_id: u32,  // Which trial is being submitted (1, 2, 3...)
-    ) {
+        expires_at: u64,      // Ledger timestamp after which the attestation is void
+        session_id: Option<u32>,
+    ) -> Result<(), Error> {
         player.require_auth();
 
-        // STEP 1: Check if round is locked
-        let locked: bool = env
+        // Progress is tracked per game round
+        let game_round_id: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::IsLocked)
-            .unwrap_or(false);
-
-        if locked {
-            panic_with_error!(&env, Error::RoundLocked);
-        }
+            .get(&DataKey::RoundId)
+            .unwrap_or(1);
 
-        // STEP 2: Validate nonce (anti-replay)
-        let last_nonce: u64 = env
+        // Required trials to win
+        let required: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::Nonce(player.clone()))
-            .unwrap_or(0);
+            .get(&DataKey::RequiredTrials)
+            .unwrap_or(7);
 
-        if nonce <= last_nonce {
-            panic_with_error!(&env, Error::InvalidNonce);
+        if Self::check_lockout(env.clone(), player.clone()).0 {
+            return Err(Error::LockedOut);
         }
 
+        // STEP 1: Find where the trial counts; check if session or round is closed
+        let session = match session_id {
+            Some(session_id) => Some(Self::session_for_player(&env, session_id, &player)?),
+            None => None,
+        };
+        let (progress_key, trials_key) = match &session {
+            Some(session) => (
+                DataKey::SessionProgress(session.session_id, player.clone()),
+                DataKey::SessionTrials(session.session_id, player.clone()),
+            ),
+            None => {
+                if Self::get_king_for_round(env.clone(), game_round_id) == Some(player.clone()) {
+                    // Already won this round
+                    return Err(Error::AlreadyKing);
+                }
+
+                let locked: bool = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::IsLocked)
+                    .unwrap_or(false);
+
+                if locked {
+                    return Err(Error::RoundLocked);
+                }
+
+                (
+                    DataKey::RoundProgress(game_round_id, player.clone()),
+                    DataKey::RoundTrials(game_round_id, player.clone()),
+                )
+            }
+        };
+
+        // STEP 2: Validate nonce (anti-replay, out of order within the window)
+        let nonce_window = Self::next_nonce_window(&env, &player, nonce)?;
+
         // STEP 3: Get player's current progress
         let current_progress: u32 = env
             .storage()
-            .instance()
-            .get(&DataKey::Progress(player.clone()))
+            .persistent()
+            .get(&progress_key)
             .unwrap_or(0);
 
-        // STEP 4: Validate trial_round_id matches expected progress
-        // Player with 0 trials complete should submit trial 1, etc.
-        let expected_trial = current_progress + 1;
-        if trial_round_id != expected_trial {
-            panic_with_error!(&env, Error::InvalidNonce); // Reuse InvalidNonce for wrong trial order
+        if session.is_some() && current_progress >= required {
+            return Err(Error::AlreadyFinished);
         }
 
-        // STEP 5: Get backend public key
-        let backend_pubkey: BytesN<32> = env
-            .storage()
-            .instance()
-            .get(&DataKey::BackendPubKey)
-            .expect("Backend public key not set");
+        // STEP 4: Validate trial_round_id is a trial the player has not done
+        // Trials 1..=required can be completed in any order
+        if trial_round_id == 0 || trial_round_id > required {
+            return Err(Error::InvalidTrial);
+        }
+
+        let trial_bit = 1u64 << (trial_round_id - 1);
+        let completed = Self::completed_trials_bitmap(&env, &trials_key, current_progress);
+        if completed & trial_bit != 0 {
+            return Err(Error::TrialAlreadyCompleted);
+        }
+
+        // STEP 5: Build the attested message using the trial_round_id
+        // Message format: see the throne-attestation crate
+        if env.ledger().timestamp() > expires_at {
+            return Err(Error::AttestationExpired);
+        }
 
-        // STEP 6: Verify backend signature using the trial_round_id
-        // Message format: SHA256(trialRoundId + player + solutionHash + nonce)
-        Self::verify_signature(
+        let message = Self::attestation_message(
             &env,
-            &backend_pubkey,
-            &signature,
+            game_round_id,
             trial_round_id,
             &player,
             &solution_hash,
             nonce,
+            expires_at,
         );
 
+        // STEP 6: Verify backend signatures (M of N)
+        Self::verify_signatures(&env, &message, &signatures)?;
+
         // STEP 6: Update nonce
-        env.storage()
-            .instance()
-            .set(&DataKey::Nonce(player.clone()), &nonce);
+        Self::store_nonce_window(&env, &player, nonce_window);
 
         // STEP 7: Update player progress
-        let mut count: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::Progress(player.clone()))
-            .unwrap_or(0);
-
-        count += 1;
-
-        env.storage()
-            .instance()
-            .set(&DataKey::Progress(player.clone()), &count);
+        let count = current_progress + 1;
+
+        env.storage().persistent().set(&progress_key, &count);
+        Self::extend_persistent_ttl(&env, &progress_key);
+        env.storage().persistent().set(&trials_key, &(completed | trial_bit));
+        Self::extend_persistent_ttl(&env, &trials_key);
+        Self::extend_instance_ttl(&env);
+
+        if let Some(session) = &session {
+            let last_trial_key = DataKey::SessionLastTrial(session.session_id, player.clone());
+            env.storage()
+                .persistent()
+                .set(&last_trial_key, &env.ledger().timestamp());
+            Self::extend_persistent_ttl(&env, &last_trial_key);
+        }
 
-        // STEP 8: Check if player becomes king
-        let required: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::RequiredTrials)
-            .unwrap_or(7);
+        // STEP 8: Check if player becomes king (or finishes their session)
+        if count < required {
+            // Progress event with trial info
+            env.events().publish(
+                (symbol_short!("progress"),),
+                (player, count, required, trial_round_id),
+            );
+        } else if let Some(session) = session {
+            // Player finishes the session; the first to do so wins it
+            env.events().publish(
+                (symbol_short!("finished"),),
+                (session.session_id, player.clone()),
+            );
 
-        if count >= required {
+            if session.winner.is_none() {
+                Self::finish_session(&env, session, &player)?;
+            }
+        } else {
             // Player becomes king
-            env.storage().instance().set(&DataKey::King, &player);
+            let king_key = DataKey::King(game_round_id);
+            env.storage().persistent().set(&king_key, &player);
+            Self::extend_persistent_ttl(&env, &king_key);
             env.storage().instance().set(&DataKey::IsLocked, &true);
 
-            // Get game round ID for event
-            let game_round_id: u32 = env
-                .storage()
-                .instance()
-                .get(&DataKey::RoundId)
-                .unwrap_or(1);
-
-            // HACKATHON REQUIREMENT: Call Game Hub's end_game() when winner determined
-            // Check if this is a multiplayer session
-            let session_started: bool = env
-                .storage()
-                .instance()
-                .get(&DataKey::SessionStarted)
-                .unwrap_or(false);
+            env.events().publish(
+                (symbol_short!("king"),),
+                (player.clone(), game_round_id, trial_round_id),
+            );
+        }
 
-            if session_started {
-                let session_id: u32 = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::SessionId)
-                    .unwrap_or(0);
+        Ok(())
+    }
 
-                // Get all players in session
-                let all_players: Vec<Address> = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::SessionAllPlayers)
-                    .unwrap_or(Vec::new(&env));
+    /// Record a failed trial attested by the backend, locking the player out
+    /// Returns the ledger timestamp the lockout ends
+    ///
+    /// The backend signatures authorize the failure, so anyone may relay it.
+    /// It shares the player's nonce sequence with `submit_proof`. With an
+    /// escalating lockout config, each further failure in the game round
+    /// doubles the lockout up to its cap.
+    pub fn record_failure(
+        env: Env,
+        player: Address,
+        trial_round_id: u32,
+        signatures: Vec<(u32, BytesN<64>)>,
+        nonce: u64,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        let game_round_id = Self::get_round_id(env.clone());
 
-                // Only report to Game Hub if winner is one of the first 2 players
-                // (Game Hub only tracks 2 players)
-                let player1: Address = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::SessionPlayer1)
-                    .expect("Session player1 not found");
+        if trial_round_id == 0 || trial_round_id > Self::get_required_trials(env.clone()) {
+            return Err(Error::InvalidTrial);
+        }
 
-                let player2: Address = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::SessionPlayer2)
-                    .expect("Session player2 not found");
-
-                // Check if winner is player1 or player2 (tracked by Game Hub)
-                let is_tracked_player = player == player1 || player == player2;
-
-                if is_tracked_player {
-                    // Winner is one of the Game Hub tracked players
-                    let player1_won = player == player1;
-
-                    // Get Game Hub address and report game end
-                    let game_hub_addr: Address = env
-                        .storage()
-                        .instance()
-                        .get(&DataKey::GameHub)
-                        .expect("Game Hub not configured");
-
-                    let game_hub_client = GameHubClient::new(&env, &game_hub_addr);
-                    game_hub_client.end_game(&session_id, &player1_won);
-
-                    env.events().publish(
-                        (symbol_short!("gameend"),),
-                        (session_id, player.clone(), player1_won),
-                    );
-                } else {
-                    // Winner is player 3 or 4 - they won but Game Hub doesn't track them
-                    // This is fine - the game still works, just no Game Hub report
-                    env.events().publish(
-                        (symbol_short!("winner"),),
-                        (session_id, player.clone()),
-                    );
-                }
-            }
+        let nonce_window = Self::next_nonce_window(&env, &player, nonce)?;
 
-            env.events().publish(
-                (symbol_short!("king"),),
-                (player.clone(), game_round_id, trial_round_id),
-            );
-        } else {
-            // Progress event with trial info
-            env.events().publish(
-                (symbol_short!("progress"),),
-                (player, count, required, trial_round_id),
-            );
+        if env.ledger().timestamp() > expires_at {
+            return Err(Error::AttestationExpired);
         }
+
+        let message =
+            Self::failure_message(&env, game_round_id, trial_round_id, &player, nonce, expires_at);
+        Self::verify_signatures(&env, &message, &signatures)?;
+
+        Self::store_nonce_window(&env, &player, nonce_window);
+
+        // Escalation starts over each game round
+        let failures_key = DataKey::Failures(player.clone());
+        let failures = match env.storage().persistent().get::<_, FailureRecord>(&failures_key) {
+            Some(record) if record.round_id == game_round_id => record.failures + 1,
+            _ => 1,
+        };
+
+        let config = Self::get_lockout_config(env.clone());
+        let duration = if config.escalating {
+            let factor = 1u64.checked_shl(failures - 1).unwrap_or(u64::MAX);
+            config.duration.saturating_mul(factor).min(config.max_duration)
+        } else {
+            config.duration
+        };
+
+        let now = env.ledger().timestamp();
+        let locked_until = now.saturating_add(duration);
+        let record = FailureRecord {
+            round_id: game_round_id,
+            failures,
+            last_failed_at: now,
+            locked_until,
+        };
+        env.storage().persistent().set(&failures_key, &record);
+        Self::extend_persistent_ttl(&env, &failures_key);
+        Self::extend_instance_ttl(&env);
+
+        env.events().publish(
+            (symbol_short!("lockout"),),
+            (player, trial_round_id, failures, locked_until),
+        );
+
+        Ok(locked_until)
     }
 
     // ========================================================================
     // SIGNATURE VERIFICATION
     // ========================================================================
 
-    /// Verify backend Ed25519 signature
-    /// Message: SHA256(roundId + player + solutionHash + nonce)
-    fn verify_signature(
+    /// Build the message backend keys sign for an attestation
+    /// Message: SHA256(versioned TrialAttestation encoding), bound to this
+    /// network, this contract and the game round
+    fn attestation_message(
         env: &Env,
-        backend_pubkey: &BytesN<32>,
-        signature: &BytesN<64>,
-        round_id: u32,
+        game_round: u32,
+        trial_id: u32,
         player: &Address,
         solution_hash: &BytesN<32>,
         nonce: u64,
-    ) {
-        // Construct message payload
-        // Format: roundId (u32) + player (string) + solutionHash (32 bytes) + nonce (u64)
+        expires_at: u64,
+    ) -> Bytes {
+        let mut contract_buf = [0u8; STRKEY_MAX_LEN];
+        let mut player_buf = [0u8; STRKEY_MAX_LEN];
+        let contract_strkey = env.current_contract_address().to_string();
+        let contract = Self::strkey_bytes(&contract_strkey, &mut contract_buf);
+        let player = Self::strkey_bytes(&player.to_string(), &mut player_buf);
+
+        let attestation = TrialAttestation {
+            network_id: env.ledger().network_id().to_array(),
+            contract,
+            game_round,
+            trial_id,
+            player,
+            solution_hash: solution_hash.to_array(),
+            nonce,
+            expires_at,
+        };
 
         let mut message = Bytes::new(env);
+        attestation.encode(|chunk| message.extend_from_slice(chunk));
 
-        // Add round_id (4 bytes, big-endian)
-        message.extend_from_array(&round_id.to_be_bytes());
+        // Hash the message with SHA-256
+        let message_hash = env.crypto().sha256(&message);
 
-        // Add player address (convert to bytes)
-        let player_string = player.to_string();
-        let player_bytes = player_string.to_bytes();
-        message.append(&player_bytes);
+        // Convert hash to Bytes for signature verification
+        Bytes::from_array(env, &message_hash.to_array())
+    }
 
-        // Add solution hash (32 bytes)
-        message.extend_from_slice(&solution_hash.to_array());
+    /// Build the message backend keys sign for a failed trial
+    /// Message: SHA256(versioned FailureAttestation encoding)
+    fn failure_message(
+        env: &Env,
+        game_round: u32,
+        trial_id: u32,
+        player: &Address,
+        nonce: u64,
+        expires_at: u64,
+    ) -> Bytes {
+        let mut contract_buf = [0u8; STRKEY_MAX_LEN];
+        let mut player_buf = [0u8; STRKEY_MAX_LEN];
+        let contract_strkey = env.current_contract_address().to_string();
+        let contract = Self::strkey_bytes(&contract_strkey, &mut contract_buf);
+        let player = Self::strkey_bytes(&player.to_string(), &mut player_buf);
+
+        let failure = FailureAttestation {
+            network_id: env.ledger().network_id().to_array(),
+            contract,
+            game_round,
+            trial_id,
+            player,
+            nonce,
+            expires_at,
+        };
 
-        // Add nonce (8 bytes, big-endian)
-        message.extend_from_array(&nonce.to_be_bytes());
+        let mut message = Bytes::new(env);
+        failure.encode(|chunk| message.extend_from_slice(chunk));
 
-        // Hash the message with SHA-256
-        let message_hash = env.crypto().sha256(&message);
+        Bytes::from_array(env, &env.crypto().sha256(&message).to_array())
+    }
 
-        // Convert hash to Bytes for signature verification
-        let hash_bytes = Bytes::from_array(env, &message_hash.to_array());
+    /// Copy a strkey into `buf`, returning the used part
+    fn strkey_bytes<'a>(strkey: &String, buf: &'a mut [u8; STRKEY_MAX_LEN]) -> &'a [u8] {
+        let len = strkey.len() as usize;
+        strkey.copy_into_slice(&mut buf[..len]);
+        &buf[..len]
+    }
 
-        // Verify Ed25519 signature
-        env.crypto()
-            .ed25519_verify(backend_pubkey, &hash_bytes, signature);
+    /// Verify Ed25519 signatures from at least the attestation threshold of
+    /// distinct, currently valid backend keys
+    fn verify_signatures(
+        env: &Env,
+        message: &Bytes,
+        signatures: &Vec<(u32, BytesN<64>)>,
+    ) -> Result<(), Error> {
+        let threshold = Self::get_attestation_threshold(env.clone());
+        if signatures.len() < threshold {
+            return Err(Error::InsufficientSignatures);
+        }
+
+        let mut signers: Vec<u32> = Vec::new(env);
+        for (key_id, signature) in signatures.iter() {
+            if signers.contains(key_id) {
+                return Err(Error::DuplicateSigner);
+            }
+            signers.push_back(key_id);
+
+            let backend_pubkey = Self::active_backend_pubkey(env, key_id)?;
+
+            // Traps if the signature is invalid
+            env.crypto()
+                .ed25519_verify(&backend_pubkey, message, &signature);
+        }
+
+        Ok(())
     }
 
     // ========================================================================
     // QUERY METHODS
     // ========================================================================
 
-    /// Get the current king
+    /// Get the king of the current round
     pub fn get_king(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::King)
+        let round_id = Self::get_round_id(env.clone());
+        Self::get_king_for_round(env, round_id)
+    }
+
+    /// Get the king of any round (past rounds are kept for auditing)
+    pub fn get_king_for_round(env: Env, round_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::King(round_id))
     }
 
-    /// Get player's progress (completed trials)
+    /// Get player's progress in the current round (completed trials)
     pub fn get_progress(env: Env, player: Address) -> u32 {
+        let round_id = Self::get_round_id(env.clone());
+        Self::get_progress_for_round(env, round_id, player)
+    }
+
+    /// Get player's progress in any round (completed trials)
+    pub fn get_progress_for_round(env: Env, round_id: u32, player: Address) -> u32 {
         env.storage()
-            .instance()
-            .get(&DataKey::Progress(player))
+            .persistent()
+            .get(&DataKey::RoundProgress(round_id, player))
             .unwrap_or(0)
     }
 
-    /// Get player's current nonce
+    /// Get the trial IDs a player has completed in the current round, ascending
+    pub fn get_completed_trials(env: Env, player: Address) -> Vec<u32> {
+        let round_id = Self::get_round_id(env.clone());
+        let progress = Self::get_progress_for_round(env.clone(), round_id, player.clone());
+        let bitmap =
+            Self::completed_trials_bitmap(&env, &DataKey::RoundTrials(round_id, player), progress);
+        Self::trial_ids(&env, bitmap)
+    }
+
+    /// Get player's highest used nonce
     pub fn get_nonce(env: Env, player: Address) -> u64 {
         env.storage()
-            .instance()
+            .persistent()
             .get(&DataKey::Nonce(player))
             .unwrap_or(0)
     }
 
+    /// Check whether a nonce can no longer be used by a player
+    /// (already used, or too far below the highest used nonce)
+    pub fn is_nonce_used(env: Env, player: Address, nonce: u64) -> bool {
+        Self::next_nonce_window(&env, &player, nonce).is_err()
+    }
+
     /// Get current round ID
     pub fn get_round_id(env: Env) -> u32 {
         env.storage().instance().get(&DataKey::RoundId).unwrap_or(1)
@@ -459,36 +1189,196 @@ impl Throne {
             .unwrap_or(7)
     }
 
-    /// Get backend public key
-    pub fn get_backend_pubkey(env: Env) -> BytesN<32> {
+    /// Get a backend signing key
+    pub fn get_backend_key(env: Env, key_id: u32) -> Option<BackendKey> {
+        env.storage().persistent().get(&DataKey::BackendKey(key_id))
+    }
+
+    /// Get the number of backend signatures required per attestation
+    pub fn get_attestation_threshold(env: Env) -> u32 {
         env.storage()
             .instance()
-            .get(&DataKey::BackendPubKey)
-            .expect("Backend public key not set")
+            .get(&DataKey::AttestationThreshold)
+            .unwrap_or(1)
+    }
+
+    /// Get the number of backend keys ever added (key IDs are 0..count)
+    pub fn get_backend_key_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BackendKeyCount)
+            .unwrap_or(0)
     }
 
     /// Get Game Hub address
-    pub fn get_game_hub(env: Env) -> Address {
+    pub fn get_game_hub(env: Env) -> Result<Address, Error> {
         env.storage()
             .instance()
             .get(&DataKey::GameHub)
-            .expect("Game Hub not configured")
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get a session
+    pub fn get_session(env: Env, session_id: u32) -> Option<Session> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Session(session_id))
+    }
+
+    /// Get the session a player has joined and not yet finished
+    pub fn get_player_session(env: Env, player: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PlayerSession(player))
+    }
+
+    /// Get all players in a session (2-4 players)
+    pub fn get_session_players(env: Env, session_id: u32) -> Vec<Address> {
+        Self::get_session(env.clone(), session_id)
+            .map(|session| session.players)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get a player's completed trials in a session
+    pub fn get_session_progress(env: Env, session_id: u32, player: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SessionProgress(session_id, player))
+            .unwrap_or(0)
+    }
+
+    /// Get the trial IDs a player has completed in a session, ascending
+    pub fn get_session_completed_trials(env: Env, session_id: u32, player: Address) -> Vec<u32> {
+        let progress = Self::get_session_progress(env.clone(), session_id, player.clone());
+        let trials_key = DataKey::SessionTrials(session_id, player);
+        let bitmap = Self::completed_trials_bitmap(&env, &trials_key, progress);
+        Self::trial_ids(&env, bitmap)
+    }
+
+    /// Get the question variant seed of a started session, derived from its
+    /// players' revealed secrets
+    pub fn get_session_seed(env: Env, session_id: u32) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SessionSeed(session_id))
+    }
+
+    /// Get the question variant a session uses for a trial, out of
+    /// `variant_count` variants
+    /// Variant = first 8 bytes of SHA256(seed || trial_id as u32 BE), as a
+    /// big-endian u64, modulo `variant_count`; anyone can recompute it from
+    /// `get_session_seed`.
+    pub fn get_trial_variant(
+        env: Env,
+        session_id: u32,
+        trial_id: u32,
+        variant_count: u32,
+    ) -> Option<u32> {
+        let seed = Self::get_session_seed(env.clone(), session_id)?;
+        if variant_count == 0 {
+            return None;
+        }
+
+        let mut preimage = Bytes::from_array(&env, &seed.to_array());
+        preimage.extend_from_array(&trial_id.to_be_bytes());
+        let digest = env.crypto().sha256(&preimage).to_array();
+
+        let mut head = [0u8; 8];
+        head.copy_from_slice(&digest[..8]);
+        Some((u64::from_be_bytes(head) % variant_count as u64) as u32)
+    }
+
+    /// Get every participant's progress and finish time, in join order
+    pub fn get_session_results(env: Env, session_id: u32) -> Result<Vec<SessionResult>, Error> {
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+        let required = Self::get_required_trials(env.clone());
+
+        let mut results = Vec::new(&env);
+        for player in session.players.iter() {
+            let progress = Self::get_session_progress(env.clone(), session_id, player.clone());
+            let last_trial_at: Option<u64> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SessionLastTrial(session_id, player.clone()));
+
+            results.push_back(SessionResult {
+                progress,
+                last_trial_at,
+                finished_at: last_trial_at.filter(|_| progress >= required),
+                forfeited: session.forfeited.contains(&player),
+                points: Self::points_of(&env, &session, &player),
+                player,
+            });
+        }
+
+        Ok(results)
     }
 
-    /// Get current session ID
-    pub fn get_session_id(env: Env) -> u32 {
+    /// Get the points a player stakes in a session
+    /// Before the start, players who declared none show the current default
+    pub fn get_session_points(env: Env, session_id: u32, player: Address) -> Result<i128, Error> {
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+        if !session.players.contains(&player) {
+            return Err(Error::NotSessionPlayer);
+        }
+        Ok(Self::points_of(&env, &session, &player))
+    }
+
+    /// Get the token session wagers are paid in and the admin fee
+    pub fn get_wager_config(env: Env) -> Option<WagerConfig> {
+        env.storage().instance().get(&DataKey::WagerConfig)
+    }
+
+    /// Get the points staked by players who declare none
+    pub fn get_default_points(env: Env) -> i128 {
         env.storage()
             .instance()
-            .get(&DataKey::SessionId)
+            .get(&DataKey::DefaultPoints)
             .unwrap_or(0)
     }
 
-    /// Get all players in current session (2-4 players)
-    pub fn get_session_players(env: Env) -> Vec<Address> {
+    /// Check whether a player is locked out by a failed trial
+    /// Returns (locked, seconds remaining)
+    pub fn check_lockout(env: Env, player: Address) -> (bool, u64) {
+        let record: Option<FailureRecord> =
+            env.storage().persistent().get(&DataKey::Failures(player));
+        let now = env.ledger().timestamp();
+        match record {
+            Some(record) if now < record.locked_until => (true, record.locked_until - now),
+            _ => (false, 0),
+        }
+    }
+
+    /// Get a player's failed trials (failures count towards `round_id` only)
+    pub fn get_failure_record(env: Env, player: Address) -> Option<FailureRecord> {
+        env.storage().persistent().get(&DataKey::Failures(player))
+    }
+
+    /// Get how long failed trials lock players out
+    pub fn get_lockout_config(env: Env) -> LockoutConfig {
         env.storage()
             .instance()
-            .get(&DataKey::SessionAllPlayers)
-            .unwrap_or(Vec::new(&env))
+            .get(&DataKey::LockoutConfig)
+            .unwrap_or(LockoutConfig {
+                duration: DEFAULT_LOCKOUT_DURATION,
+                escalating: false,
+                max_duration: DEFAULT_LOCKOUT_DURATION,
+            })
+    }
+
+    /// Get the Game Hub calls of a session awaiting `retry_hub_report`
+    pub fn get_pending_hub_report(env: Env, session_id: u32) -> Option<PendingHubReport> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingHubReport(session_id))
+    }
+
+    /// Get how long a started session runs before it can be expired (seconds)
+    pub fn get_session_duration(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SessionDuration)
+            .unwrap_or(DEFAULT_SESSION_DURATION)
     }
 
     // ========================================================================
@@ -496,9 +1386,10 @@ impl Throne {
     // ========================================================================
 
     /// Start a new round (admin only)
-    /// Resets game state for a fresh competition
-    pub fn start_new_round(env: Env, admin: Address) {
-        admin.require_auth();
+    /// Players start the new round with no progress; kings and progress of
+    /// previous rounds are kept. Sessions are not affected.
+    pub fn start_new_round(env: Env, admin: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
 
         let current_round: u32 = env
             .storage()
@@ -510,17 +1401,685 @@ impl Throne {
 
         env.storage().instance().set(&DataKey::RoundId, &new_round);
         env.storage().instance().set(&DataKey::IsLocked, &false);
-        env.storage().instance().remove(&DataKey::King);
-        
-        // Reset session data for new round
-        env.storage().instance().set(&DataKey::SessionStarted, &false);
-        env.storage().instance().remove(&DataKey::SessionId);
-        env.storage().instance().remove(&DataKey::SessionPlayer1);
-        env.storage().instance().remove(&DataKey::SessionPlayer2);
-        env.storage().instance().remove(&DataKey::SessionAllPlayers);
 
         env.events()
             .publish((symbol_short!("newround"),), (new_round,));
+
+        Ok(())
+    }
+
+    /// Add a backend signing key (admin only)
+    /// Lets a new key be rolled out before the old one expires or is revoked
+    ///
+    /// # Arguments
+    /// * `pubkey` - Ed25519 public key
+    /// * `active_from` - First ledger the key is valid
+    /// * `expires_at` - First ledger the key is no longer valid (None = never)
+    ///
+    /// # Returns
+    /// * The new key's ID
+    pub fn add_backend_key(
+        env: Env,
+        admin: Address,
+        pubkey: BytesN<32>,
+        active_from: u32,
+        expires_at: Option<u32>,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key_id = Self::store_backend_key(&env, &pubkey, active_from, expires_at);
+
+        env.events()
+            .publish((symbol_short!("keyadd"),), (key_id, pubkey, active_from, expires_at));
+
+        Ok(key_id)
+    }
+
+    /// Revoke a backend signing key immediately (admin only)
+    /// Rejected if fewer valid keys than the attestation threshold would remain
+    pub fn revoke_backend_key(env: Env, admin: Address, key_id: u32) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::BackendKey(key_id);
+        let mut backend_key: BackendKey = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::UnknownBackendKey)?;
+
+        // Keep enough valid keys to meet the attestation threshold
+        if Self::backend_key_is_valid(&backend_key, env.ledger().sequence())
+            && Self::valid_backend_key_count(&env) <= Self::get_attestation_threshold(env.clone())
+        {
+            return Err(Error::BelowThreshold);
+        }
+
+        backend_key.revoked = true;
+        env.storage().persistent().set(&key, &backend_key);
+        Self::extend_persistent_ttl(&env, &key);
+
+        env.events()
+            .publish((symbol_short!("keyrevoke"),), (key_id,));
+
+        Ok(())
+    }
+
+    /// Set the token session wagers are paid in and the admin fee (admin only)
+    /// Sessions keep the token and fee they were created with
+    pub fn set_wager_config(
+        env: Env,
+        admin: Address,
+        token: Address,
+        fee_bps: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if fee_bps > MAX_FEE_BPS {
+            return Err(Error::InvalidFee);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WagerConfig, &WagerConfig { token: token.clone(), fee_bps });
+
+        env.events()
+            .publish((symbol_short!("wagercfg"),), (token, fee_bps));
+
+        Ok(())
+    }
+
+    /// Set how long failed trials lock players out (admin only)
+    /// Escalating lockouts double with each further failure in a game round,
+    /// up to `max_duration`. A zero duration disables lockouts.
+    pub fn set_lockout_config(
+        env: Env,
+        admin: Address,
+        duration: u64,
+        escalating: bool,
+        max_duration: u64,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if max_duration < duration {
+            return Err(Error::InvalidLockout);
+        }
+
+        let config = LockoutConfig {
+            duration,
+            escalating,
+            max_duration,
+        };
+        env.storage().instance().set(&DataKey::LockoutConfig, &config);
+
+        env.events()
+            .publish((symbol_short!("lockcfg"),), (duration, escalating, max_duration));
+
+        Ok(())
+    }
+
+    /// Set the points staked by players who declare none (admin only)
+    /// Applies to sessions started afterwards
+    pub fn set_default_points(env: Env, admin: Address, points: i128) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if points < 0 {
+            return Err(Error::InvalidPoints);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::DefaultPoints, &points);
+
+        env.events()
+            .publish((symbol_short!("defpoints"),), (points,));
+
+        Ok(())
+    }
+
+    /// Set how long started sessions run before they can be expired (admin only)
+    /// Applies to sessions started afterwards
+    pub fn set_session_duration(env: Env, admin: Address, duration: u64) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SessionDuration, &duration);
+
+        env.events()
+            .publish((symbol_short!("duration"),), (duration,));
+
+        Ok(())
+    }
+
+    /// Require signatures from `threshold` distinct backend keys per
+    /// attestation (admin only)
+    /// Lets independent verifier services each hold one key. At most the
+    /// number of keys valid at the current ledger.
+    pub fn set_attestation_threshold(
+        env: Env,
+        admin: Address,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if threshold == 0 || threshold > Self::valid_backend_key_count(&env) {
+            return Err(Error::InvalidThreshold);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AttestationThreshold, &threshold);
+
+        env.events()
+            .publish((symbol_short!("threshold"),), (threshold,));
+
+        Ok(())
+    }
+
+    /// Propose a new admin (admin only)
+    /// The new admin must call accept_admin to complete the transfer
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        env.events()
+            .publish((symbol_short!("adminprop"),), (admin, new_admin));
+
+        Ok(())
+    }
+
+    /// Accept a pending admin transfer (proposed admin only)
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        new_admin.require_auth();
+
+        let pending: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+
+        if pending != new_admin {
+            return Err(Error::NotAdmin);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        env.events()
+            .publish((symbol_short!("newadmin"),), (new_admin,));
+
+        Ok(())
+    }
+
+    /// Get the current admin
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Admin)
+    }
+
+    /// Get the proposed admin awaiting acceptance
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Replace the contract code with an uploaded Wasm (admin only)
+    ///
+    /// Storage is kept, so new code must read the layout it replaces.
+    /// The first release has no upgrade entry point and kept player data in
+    /// instance storage: its deployments must be redeployed, not upgraded.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // INTERNAL HELPERS
+    // ========================================================================
+
+    /// Get a session that is still accepting players
+    fn waiting_session(env: &Env, session_id: u32) -> Result<Session, Error> {
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+        if session.status != SessionStatus::Waiting {
+            return Err(Error::SessionNotWaiting);
+        }
+        Ok(session)
+    }
+
+    /// Get a waiting session whose players can still join, leave and change
+    /// readiness, i.e. no seed secret has been revealed yet
+    fn open_lobby(env: &Env, session_id: u32) -> Result<Session, Error> {
+        let session = Self::waiting_session(env, session_id)?;
+        if !session.seed_reveals.is_empty() {
+            return Err(Error::SeedRevealStarted);
+        }
+        Ok(session)
+    }
+
+    /// Whether the seed reveal window of a session has started and closed
+    fn reveal_window_over(env: &Env, session: &Session) -> bool {
+        session
+            .reveal_by
+            .is_some_and(|reveal_by| env.ledger().timestamp() > reveal_by)
+    }
+
+    /// Get a started session that the player takes part in and can still play
+    fn session_for_player(
+        env: &Env,
+        session_id: u32,
+        player: &Address,
+    ) -> Result<Session, Error> {
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+        // Finished sessions stay open so the runner-up can still finish
+        match session.status {
+            SessionStatus::Waiting => return Err(Error::SessionNotStarted),
+            SessionStatus::Abandoned => return Err(Error::SessionOver),
+            SessionStatus::Countdown | SessionStatus::Finished => {}
+        }
+        if !session.players.contains(player) {
+            return Err(Error::NotSessionPlayer);
+        }
+        if session.forfeited.contains(player) {
+            return Err(Error::AlreadyForfeited);
+        }
+        if session
+            .deadline
+            .is_some_and(|deadline| env.ledger().timestamp() > deadline)
+        {
+            return Err(Error::SessionExpired);
+        }
+        Ok(session)
+    }
+
+    /// Get a started session that has no winner yet
+    fn running_session(env: &Env, session_id: u32) -> Result<Session, Error> {
+        let session = Self::get_session(env.clone(), session_id).ok_or(Error::SessionNotFound)?;
+        match session.status {
+            SessionStatus::Waiting => Err(Error::SessionNotStarted),
+            SessionStatus::Countdown => Ok(session),
+            SessionStatus::Finished | SessionStatus::Abandoned => Err(Error::SessionOver),
+        }
+    }
+
+    /// Record that a player takes part in a session
+    fn enter_session(env: &Env, player: &Address, session_id: u32) -> Result<(), Error> {
+        let key = DataKey::PlayerSession(player.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyJoined);
+        }
+        env.storage().persistent().set(&key, &session_id);
+        Self::extend_persistent_ttl(env, &key);
+        Ok(())
+    }
+
+    /// Persist a session
+    fn save_session(env: &Env, session: &Session) {
+        let key = DataKey::Session(session.session_id);
+        env.storage().persistent().set(&key, session);
+        Self::extend_persistent_ttl(env, &key);
+    }
+
+    /// Record the session winner, pay out the pot, release its players and
+    /// report to the Game Hub
+    fn finish_session(env: &Env, mut session: Session, winner: &Address) -> Result<(), Error> {
+        let session_id = session.session_id;
+        session.status = SessionStatus::Finished;
+        session.winner = Some(winner.clone());
+        Self::pay_out_pot(env, &mut session, winner)?;
+        Self::save_session(env, &session);
+
+        for session_player in session.players.iter() {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PlayerSession(session_player));
+        }
+
+        // HACKATHON REQUIREMENT: Call Game Hub's start_game() and end_game()
+        // now that the winner is determined. Game Hub only tracks 2 players:
+        // 2-player sessions are reported in join order, sessions of 3-4
+        // players as winner versus runner-up
+        session.hub_pair = if session.players.len() == MIN_SESSION_PLAYERS {
+            session.players.clone()
+        } else {
+            let runner_up = Self::best_player(env, &session, Some(winner))
+                .ok_or(Error::InvalidPlayerCount)?;
+            Vec::from_array(env, [winner.clone(), runner_up])
+        };
+        Self::save_session(env, &session);
+        let player1_won = session.hub_pair.first() == Some(winner.clone());
+
+        // Report game end; the hub must have seen the start first
+        let start_pending = !Self::hub_start_game(env, &session)?;
+        if start_pending || !Self::hub_end_game(env, session_id, player1_won)? {
+            Self::queue_hub_report(
+                env,
+                session_id,
+                &PendingHubReport { start_pending, player1_won: Some(player1_won) },
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("gameend"),),
+            (session_id, winner.clone(), player1_won),
+        );
+
+        Ok(())
+    }
+
+    /// End a session without a winner, refund every stake and release its
+    /// players
+    /// Nothing is reported to the Game Hub, which has not seen the session.
+    fn abandon_session(env: &Env, mut session: Session) {
+        let session_id = session.session_id;
+        let stake = session.stake;
+        for player in session.players.clone().iter() {
+            Self::release_stake(env, &mut session, &player, stake);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PlayerSession(player));
+        }
+
+        session.status = SessionStatus::Abandoned;
+        Self::save_session(env, &session);
+
+        env.events()
+            .publish((symbol_short!("abandoned"),), (session_id,));
+    }
+
+    /// Pick the strongest session player: players who have not forfeited
+    /// first, then most completed trials, then earliest latest trial; join
+    /// order breaks remaining ties
+    fn best_player(env: &Env, session: &Session, exclude: Option<&Address>) -> Option<Address> {
+        let mut best = None;
+        let mut best_rank = (false, 0, Reverse(u64::MAX));
+        for player in session.players.iter() {
+            if exclude == Some(&player) {
+                continue;
+            }
+            let last_trial_at: u64 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SessionLastTrial(session.session_id, player.clone()))
+                .unwrap_or(u64::MAX);
+            let rank = (
+                !session.forfeited.contains(&player),
+                Self::get_session_progress(env.clone(), session.session_id, player.clone()),
+                Reverse(last_trial_at),
+            );
+
+            if best.is_none() || rank > best_rank {
+                best = Some(player);
+                best_rank = rank;
+            }
+        }
+        best
+    }
+
+    /// Report a session start to the Game Hub; false if the hub call failed
+    /// NOTE: Only the session's hub pair is reported (Game Hub limitation)
+    fn hub_start_game(env: &Env, session: &Session) -> Result<bool, Error> {
+        let player1 = session.hub_pair.get(0).ok_or(Error::SessionNotFound)?;
+        let player2 = session.hub_pair.get(1).ok_or(Error::SessionNotFound)?;
+
+        let game_hub_client = GameHubClient::new(env, &Self::get_game_hub(env.clone())?);
+        let result = game_hub_client.try_start_game(
+            &env.current_contract_address(),
+            &session.session_id,
+            &player1,
+            &player2,
+            &Self::points_of(env, session, &player1),
+            &Self::points_of(env, session, &player2),
+        );
+
+        Ok(matches!(result, Ok(Ok(()))))
+    }
+
+    /// Report a session result to the Game Hub; false if the hub call failed
+    fn hub_end_game(env: &Env, session_id: u32, player1_won: bool) -> Result<bool, Error> {
+        let game_hub_client = GameHubClient::new(env, &Self::get_game_hub(env.clone())?);
+        let result = game_hub_client.try_end_game(&session_id, &player1_won);
+
+        Ok(matches!(result, Ok(Ok(()))))
+    }
+
+    /// Move a player's wager stake into escrow
+    fn escrow_stake(env: &Env, session: &mut Session, player: &Address) {
+        if let Some(wager_token) = &session.wager_token {
+            token::Client::new(env, wager_token).transfer(
+                player,
+                env.current_contract_address(),
+                &session.stake,
+            );
+            session.pot += session.stake;
+        }
+    }
+
+    /// Send `amount` of the escrowed pot to a player
+    fn release_stake(env: &Env, session: &mut Session, player: &Address, amount: i128) {
+        if let Some(wager_token) = &session.wager_token {
+            if amount > 0 {
+                token::Client::new(env, wager_token).transfer(
+                    &env.current_contract_address(),
+                    player,
+                    &amount,
+                );
+                session.pot -= amount;
+            }
+        }
+    }
+
+    /// Pay the pot to the session winner, minus the admin fee
+    fn pay_out_pot(env: &Env, session: &mut Session, winner: &Address) -> Result<(), Error> {
+        if session.pot == 0 {
+            return Ok(());
+        }
+
+        let fee = session.pot * session.fee_bps as i128 / MAX_FEE_BPS as i128;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        let prize = session.pot - fee;
+        Self::release_stake(env, session, &admin, fee);
+        Self::release_stake(env, session, winner, prize);
+
+        env.events().publish(
+            (symbol_short!("payout"),),
+            (session.session_id, winner.clone(), prize, fee),
+        );
+
+        Ok(())
+    }
+
+    /// Points a session player stakes (declared, or the default)
+    fn points_of(env: &Env, session: &Session, player: &Address) -> i128 {
+        session
+            .points
+            .get(player.clone())
+            .unwrap_or_else(|| Self::get_default_points(env.clone()))
+    }
+
+    /// Keep failed Game Hub calls for `retry_hub_report`
+    fn queue_hub_report(env: &Env, session_id: u32, pending: &PendingHubReport) {
+        let key = DataKey::PendingHubReport(session_id);
+        env.storage().persistent().set(&key, pending);
+        Self::extend_persistent_ttl(env, &key);
+
+        env.events()
+            .publish((symbol_short!("hubfail"),), (session_id, pending.clone()));
+    }
+
+    /// Store a new backend key under the next key_id
+    fn store_backend_key(
+        env: &Env,
+        pubkey: &BytesN<32>,
+        active_from: u32,
+        expires_at: Option<u32>,
+    ) -> u32 {
+        let key_id = Self::get_backend_key_count(env.clone());
+        let key = DataKey::BackendKey(key_id);
+
+        env.storage().persistent().set(
+            &key,
+            &BackendKey {
+                pubkey: pubkey.clone(),
+                active_from,
+                expires_at,
+                revoked: false,
+            },
+        );
+        Self::extend_persistent_ttl(env, &key);
+        env.storage()
+            .instance()
+            .set(&DataKey::BackendKeyCount, &(key_id + 1));
+
+        key_id
+    }
+
+    /// Get the public key of a backend key that is valid at the current ledger
+    fn active_backend_pubkey(env: &Env, key_id: u32) -> Result<BytesN<32>, Error> {
+        let key = DataKey::BackendKey(key_id);
+        let backend_key: BackendKey = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::UnknownBackendKey)?;
+
+        if !Self::backend_key_is_valid(&backend_key, env.ledger().sequence()) {
+            return Err(Error::InactiveBackendKey);
+        }
+
+        Self::extend_persistent_ttl(env, &key);
+
+        Ok(backend_key.pubkey)
+    }
+
+    /// Whether a backend key can sign at `ledger`: active, not expired or revoked
+    fn backend_key_is_valid(backend_key: &BackendKey, ledger: u32) -> bool {
+        let expired = backend_key.expires_at.is_some_and(|expires_at| ledger >= expires_at);
+        !backend_key.revoked && ledger >= backend_key.active_from && !expired
+    }
+
+    /// Number of backend keys that can sign at the current ledger
+    fn valid_backend_key_count(env: &Env) -> u32 {
+        let ledger = env.ledger().sequence();
+        (0..Self::get_backend_key_count(env.clone()))
+            .filter_map(|key_id| {
+                env.storage()
+                    .persistent()
+                    .get::<_, BackendKey>(&DataKey::BackendKey(key_id))
+            })
+            .filter(|backend_key| Self::backend_key_is_valid(backend_key, ledger))
+            .count() as u32
+    }
+
+    /// Player's nonce window after using `nonce`: (highest nonce, used bitmap)
+    /// Nonces within `NONCE_WINDOW` below the highest are accepted once, so
+    /// parallel backend signers and reordered transactions do not collide.
+    fn next_nonce_window(env: &Env, player: &Address, nonce: u64) -> Result<(u64, u64), Error> {
+        let persistent = env.storage().persistent();
+        let highest: u64 = persistent.get(&DataKey::Nonce(player.clone())).unwrap_or(0);
+        // Players from before the window have used every nonce up to their highest
+        let used: u64 = persistent
+            .get(&DataKey::NonceWindow(player.clone()))
+            .unwrap_or(if highest > 0 { u64::MAX } else { 0 });
+
+        if nonce == 0 {
+            return Err(Error::InvalidNonce);
+        }
+        if nonce > highest {
+            let shift = nonce - highest;
+            let shifted = if shift >= NONCE_WINDOW { 0 } else { used << shift };
+            return Ok((nonce, shifted | 1));
+        }
+
+        let offset = highest - nonce;
+        if offset >= NONCE_WINDOW || used & (1u64 << offset) != 0 {
+            return Err(Error::InvalidNonce);
+        }
+        Ok((highest, used | (1u64 << offset)))
+    }
+
+    /// Store a player's nonce window from `next_nonce_window`
+    fn store_nonce_window(env: &Env, player: &Address, (highest, used): (u64, u64)) {
+        let nonce_key = DataKey::Nonce(player.clone());
+        let window_key = DataKey::NonceWindow(player.clone());
+        env.storage().persistent().set(&nonce_key, &highest);
+        env.storage().persistent().set(&window_key, &used);
+        Self::extend_persistent_ttl(env, &nonce_key);
+        Self::extend_persistent_ttl(env, &window_key);
+    }
+
+    /// Bitmap of a player's completed trials
+    /// Progress recorded before trials could be done out of order has no
+    /// bitmap; it covers trials 1..=progress.
+    fn completed_trials_bitmap(env: &Env, trials_key: &DataKey, progress: u32) -> u64 {
+        env.storage()
+            .persistent()
+            .get(trials_key)
+            .unwrap_or_else(|| Self::sequential_trials(progress))
+    }
+
+    /// Bitmap of trials 1..=progress
+    fn sequential_trials(progress: u32) -> u64 {
+        match progress {
+            0 => 0,
+            p if p >= MAX_TRIALS => u64::MAX,
+            p => (1u64 << p) - 1,
+        }
+    }
+
+    /// Trial IDs set in a completed trials bitmap, ascending
+    fn trial_ids(env: &Env, bitmap: u64) -> Vec<u32> {
+        let mut ids = Vec::new(env);
+        for trial_id in 1..=MAX_TRIALS {
+            if bitmap & (1u64 << (trial_id - 1)) != 0 {
+                ids.push_back(trial_id);
+            }
+        }
+        ids
+    }
+
+    /// Keep a persistent entry alive
+    fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LEDGER_TTL_THRESHOLD, LEDGER_TTL_EXTENSION);
+    }
+
+    /// Keep the contract instance (and its config) alive
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTENSION);
+    }
+
+    /// Require auth from `admin` and check it is the stored admin
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        if *admin != stored_admin {
+            return Err(Error::NotAdmin);
+        }
+
+        Ok(())
     }
 }
 
+mod test;
@@ -0,0 +1,1419 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env,
+};
+use throne_attestation::{FailureAttestation, TrialAttestation};
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+const BACKEND_SECRET: [u8; 32] = [7u8; 32];
+const EXPIRES_AT: u64 = 1_000;
+
+fn backend_key() -> SigningKey {
+    SigningKey::from_bytes(&BACKEND_SECRET)
+}
+
+/// Game Hub stand-in that remembers the last reported start and end
+/// and can be taken down to make every call trap
+#[contract]
+struct MockGameHub;
+
+#[contractimpl]
+impl MockGameHub {
+    pub fn set_down(env: Env, down: bool) {
+        env.storage().instance().set(&symbol_short!("down"), &down);
+    }
+
+    pub fn start_game(
+        env: Env,
+        _game_id: Address,
+        session_id: u32,
+        player1: Address,
+        player2: Address,
+        player1_points: i128,
+        player2_points: i128,
+    ) {
+        Self::fail_if_down(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("started"), &(session_id, player1, player2));
+        env.storage()
+            .instance()
+            .set(&symbol_short!("points"), &(player1_points, player2_points));
+    }
+
+    pub fn end_game(env: Env, session_id: u32, player1_won: bool) {
+        Self::fail_if_down(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ended"), &(session_id, player1_won));
+    }
+
+    fn fail_if_down(env: &Env) {
+        let down: bool = env.storage().instance().get(&symbol_short!("down")).unwrap_or(false);
+        assert!(!down, "game hub down");
+    }
+}
+
+fn set_hub_down(env: &Env, client: &ThroneClient, down: bool) {
+    MockGameHubClient::new(env, &client.get_game_hub()).set_down(&down);
+}
+
+fn hub_started(env: &Env, client: &ThroneClient) -> Option<(u32, Address, Address)> {
+    let hub = client.get_game_hub();
+    env.as_contract(&hub, || env.storage().instance().get(&symbol_short!("started")))
+}
+
+fn hub_points(env: &Env, client: &ThroneClient) -> Option<(i128, i128)> {
+    let hub = client.get_game_hub();
+    env.as_contract(&hub, || env.storage().instance().get(&symbol_short!("points")))
+}
+
+fn hub_ended(env: &Env, client: &ThroneClient) -> Option<(u32, bool)> {
+    let hub = client.get_game_hub();
+    env.as_contract(&hub, || env.storage().instance().get(&symbol_short!("ended")))
+}
+
+fn setup(env: &Env) -> (ThroneClient<'_>, Address) {
+    let contract_id = env.register(Throne, ());
+    let client = ThroneClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let backend_pubkey = BytesN::from_array(env, &backend_key().verifying_key().to_bytes());
+    let game_hub = env.register(MockGameHub, ());
+
+    env.mock_all_auths();
+
+    client.initialize(&admin, &backend_pubkey, &3, &game_hub);
+
+    (client, admin)
+}
+
+/// Render an address as strkey bytes
+fn strkey(address: &Address) -> std::vec::Vec<u8> {
+    let strkey = address.to_string();
+    let mut bytes = std::vec![0u8; strkey.len() as usize];
+    strkey.copy_into_slice(&mut bytes);
+    bytes
+}
+
+/// Sign an attestation the way a Rust signer does, once per `(key_id, key)`
+/// Valid until `EXPIRES_AT` in the client contract's current round
+fn attest(
+    env: &Env,
+    client: &ThroneClient,
+    signers: &[(u32, &SigningKey)],
+    trial_round_id: u32,
+    player: &Address,
+    solution_hash: &BytesN<32>,
+    nonce: u64,
+) -> Vec<(u32, BytesN<64>)> {
+    let contract = strkey(&client.address);
+    let player = strkey(player);
+    let attestation = TrialAttestation {
+        network_id: env.ledger().network_id().to_array(),
+        contract: &contract,
+        game_round: client.get_round_id(),
+        trial_id: trial_round_id,
+        player: &player,
+        solution_hash: solution_hash.to_array(),
+        nonce,
+        expires_at: EXPIRES_AT,
+    };
+
+    let mut message = Bytes::new(env);
+    attestation.encode(|chunk| message.extend_from_slice(chunk));
+
+    let hash = env.crypto().sha256(&message).to_array();
+    let mut signatures = Vec::new(env);
+    for (key_id, key) in signers {
+        signatures.push_back((*key_id, BytesN::from_array(env, &key.sign(&hash).to_bytes())));
+    }
+    signatures
+}
+
+/// Backend signatures over a failed trial
+fn attest_failure(
+    env: &Env,
+    client: &ThroneClient,
+    trial_round_id: u32,
+    player: &Address,
+    nonce: u64,
+) -> Vec<(u32, BytesN<64>)> {
+    let contract = strkey(&client.address);
+    let player = strkey(player);
+    let failure = FailureAttestation {
+        network_id: env.ledger().network_id().to_array(),
+        contract: &contract,
+        game_round: client.get_round_id(),
+        trial_id: trial_round_id,
+        player: &player,
+        nonce,
+        expires_at: EXPIRES_AT,
+    };
+
+    let mut message = Bytes::new(env);
+    failure.encode(|chunk| message.extend_from_slice(chunk));
+
+    let signature = backend_key().sign(&env.crypto().sha256(&message).to_array());
+    vec![env, (0, BytesN::from_array(env, &signature.to_bytes()))]
+}
+
+/// Submit a valid attestation for the player's next trial in the round race
+fn complete_trial(env: &Env, client: &ThroneClient, player: &Address, trial_round_id: u32) {
+    complete_session_trial(env, client, player, None, trial_round_id);
+}
+
+/// Submit a valid attestation for the player's next trial
+fn complete_session_trial(
+    env: &Env,
+    client: &ThroneClient,
+    player: &Address,
+    session_id: Option<u32>,
+    trial_round_id: u32,
+) {
+    let solution_hash = BytesN::from_array(env, &[trial_round_id as u8; 32]);
+    let nonce = client.get_nonce(player) + 1;
+    let signatures = attest(
+        env,
+        client,
+        &[(0, &backend_key())],
+        trial_round_id,
+        player,
+        &solution_hash,
+        nonce,
+    );
+    client.submit_proof(
+        player,
+        &solution_hash,
+        &signatures,
+        &nonce,
+        &trial_round_id,
+        &EXPIRES_AT,
+        &session_id,
+    );
+}
+
+/// Start a session of `size` fresh players, all ready; the first one hosts
+fn start_session(
+    env: &Env,
+    client: &ThroneClient,
+    session_id: u32,
+    size: usize,
+) -> std::vec::Vec<Address> {
+    let players: std::vec::Vec<Address> = (0..size).map(|_| Address::generate(env)).collect();
+    client.create_session(&players[0], &session_id, &0, &seed_commit(env, &players[0]));
+    for player in &players[1..] {
+        client.join_session(player, &session_id, &seed_commit(env, player));
+        client.set_ready(player, &session_id, &true);
+    }
+    reveal_seeds(env, client, session_id);
+    client.start_session(&players[0], &session_id);
+    players
+}
+
+/// Seed secret a test player commits to when joining a session
+fn seed_secret(env: &Env, player: &Address) -> BytesN<32> {
+    env.crypto().sha256(&player.clone().to_xdr(env)).into()
+}
+
+fn seed_commit(env: &Env, player: &Address) -> BytesN<32> {
+    env.crypto().sha256(&seed_secret(env, player).into()).into()
+}
+
+/// Reveal every session player's seed secret
+fn reveal_seeds(env: &Env, client: &ThroneClient, session_id: u32) {
+    for player in client.get_session(&session_id).unwrap().players.iter() {
+        client.reveal_seed(&player, &session_id, &seed_secret(env, &player));
+    }
+}
+
+// ============================================================================
+// Admin
+// ============================================================================
+
+#[test]
+fn test_initialize_stores_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    assert_eq!(client.get_admin(), Some(admin));
+    assert_eq!(client.get_round_id(), 1);
+}
+
+#[test]
+fn test_reinitialize_rejected() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let attacker = Address::generate(&env);
+    let attacker_key = BytesN::from_array(&env, &[9u8; 32]);
+    let result = client.try_initialize(&attacker, &attacker_key, &1, &attacker);
+
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+    assert_eq!(
+        client.get_backend_key(&0).unwrap().pubkey.to_array(),
+        backend_key().verifying_key().to_bytes()
+    );
+}
+
+#[test]
+fn test_start_new_round_requires_stored_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_start_new_round(&stranger),
+        Err(Ok(Error::NotAdmin))
+    );
+
+    client.start_new_round(&admin);
+    assert_eq!(client.get_round_id(), 2);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let new_admin = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.propose_admin(&admin, &new_admin);
+    assert_eq!(client.get_admin(), Some(admin.clone()));
+
+    assert_eq!(client.try_accept_admin(&stranger), Err(Ok(Error::NotAdmin)));
+
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_pending_admin(), None);
+
+    // Old admin lost its rights
+    assert_eq!(client.try_start_new_round(&admin), Err(Ok(Error::NotAdmin)));
+    client.start_new_round(&new_admin);
+}
+
+// ============================================================================
+// Rounds
+// ============================================================================
+
+#[test]
+fn test_progress_is_scoped_to_round() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    for trial in 1..=3 {
+        complete_trial(&env, &client, &player, trial);
+    }
+    assert_eq!(client.get_progress(&player), 3);
+    assert_eq!(client.get_king(), Some(player.clone()));
+
+    client.start_new_round(&admin);
+
+    // Fresh start in round 2, round 1 kept for auditing
+    assert_eq!(client.get_progress(&player), 0);
+    assert_eq!(client.get_king(), None);
+    assert_eq!(client.get_progress_for_round(&1, &player), 3);
+    assert_eq!(client.get_king_for_round(&1), Some(player.clone()));
+
+    complete_trial(&env, &client, &player, 1);
+    assert_eq!(client.get_progress_for_round(&2, &player), 1);
+}
+
+#[test]
+fn test_descriptive_submission_errors() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    // Trials outside 1..=required
+    let solution_hash = BytesN::from_array(&env, &[2u8; 32]);
+    for trial in [0, 4] {
+        let signatures =
+            attest(&env, &client, &[(0, &backend_key())], trial, &player, &solution_hash, 1);
+        assert_eq!(
+            client.try_submit_proof(
+                &player,
+                &solution_hash,
+                &signatures,
+                &1,
+                &trial,
+                &EXPIRES_AT,
+                &None
+            ),
+            Err(Ok(Error::InvalidTrial))
+        );
+    }
+
+    for trial in 1..=3 {
+        complete_trial(&env, &client, &player, trial);
+    }
+
+    let solution_hash = BytesN::from_array(&env, &[4u8; 32]);
+    let signatures = attest(&env, &client, &[(0, &backend_key())], 4, &player, &solution_hash, 4);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &signatures, &4, &4, &EXPIRES_AT, &None),
+        Err(Ok(Error::AlreadyKing))
+    );
+}
+
+#[test]
+fn test_trials_completed_in_any_order() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    complete_trial(&env, &client, &player, 3);
+    complete_trial(&env, &client, &player, 1);
+    assert_eq!(client.get_progress(&player), 2);
+    assert_eq!(client.get_completed_trials(&player), vec![&env, 1, 3]);
+
+    // Same trial again, with a fresh nonce
+    let solution_hash = BytesN::from_array(&env, &[3u8; 32]);
+    let signatures = attest(&env, &client, &[(0, &backend_key())], 3, &player, &solution_hash, 3);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &signatures, &3, &3, &EXPIRES_AT, &None),
+        Err(Ok(Error::TrialAlreadyCompleted))
+    );
+
+    complete_trial(&env, &client, &player, 2);
+    assert_eq!(client.get_king(), Some(player.clone()));
+
+    // Session trials are tracked separately from the round
+    let players = start_session(&env, &client, 7, 2);
+    complete_session_trial(&env, &client, &players[0], Some(7), 2);
+    assert_eq!(client.get_session_completed_trials(&7, &players[0]), vec![&env, 2]);
+    assert_eq!(client.get_completed_trials(&players[0]), vec![&env]);
+}
+
+#[test]
+fn test_nonces_accepted_out_of_order_within_window() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let player = Address::generate(&env);
+    let signers = [(0, &backend_key())];
+
+    // Two backend instances hand out nonces 2 and 1; 2 lands first
+    for (trial, nonce) in [(1, 2), (2, 1)] {
+        let hash = BytesN::from_array(&env, &[trial as u8; 32]);
+        let sigs = attest(&env, &client, &signers, trial, &player, &hash, nonce);
+        client.submit_proof(&player, &hash, &sigs, &nonce, &trial, &EXPIRES_AT, &None);
+    }
+    assert_eq!(client.get_progress(&player), 2);
+    assert_eq!(client.get_nonce(&player), 2);
+    assert!(client.is_nonce_used(&player, &1));
+    assert!(!client.is_nonce_used(&player, &3));
+
+    // Replays and nonces that fell out of the window are rejected
+    let hash = BytesN::from_array(&env, &[3u8; 32]);
+    let replay = attest(&env, &client, &signers, 3, &player, &hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &hash, &replay, &1, &3, &EXPIRES_AT, &None),
+        Err(Ok(Error::InvalidNonce))
+    );
+
+    let failure = attest_failure(&env, &client, 3, &player, 100);
+    client.record_failure(&player, &3, &failure, &100, &EXPIRES_AT);
+    assert!(client.is_nonce_used(&player, &36));
+    assert!(!client.is_nonce_used(&player, &37));
+}
+
+#[test]
+fn test_session_requires_two_to_four_players() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+
+    client.create_session(&host, &1, &0, &seed_commit(&env, &host));
+    assert_eq!(
+        client.try_start_session(&host, &1),
+        Err(Ok(Error::InvalidPlayerCount))
+    );
+
+    for _ in 1..4 {
+        let player = Address::generate(&env);
+        client.join_session(&player, &1, &seed_commit(&env, &player));
+    }
+    let extra = Address::generate(&env);
+    assert_eq!(
+        client.try_join_session(&extra, &1, &seed_commit(&env, &extra)),
+        Err(Ok(Error::SessionFull))
+    );
+}
+
+#[test]
+fn test_session_lobby_flow() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+    let latecomer = Address::generate(&env);
+
+    client.create_session(&host, &42, &0, &seed_commit(&env, &host));
+    assert_eq!(
+        client.try_create_session(&latecomer, &42, &0, &seed_commit(&env, &latecomer)),
+        Err(Ok(Error::SessionExists))
+    );
+
+    // Joining takes the player's own signature
+    client.join_session(&guest, &42, &seed_commit(&env, &guest));
+    assert_eq!(env.auths()[0].0, guest);
+    assert_eq!(
+        client.try_join_session(&guest, &42, &seed_commit(&env, &guest)),
+        Err(Ok(Error::AlreadyJoined))
+    );
+    assert_eq!(
+        client.try_set_ready(&latecomer, &42, &true),
+        Err(Ok(Error::NotSessionPlayer))
+    );
+
+    // Everyone has to be ready, and only the host starts
+    assert_eq!(client.try_start_session(&host, &42), Err(Ok(Error::PlayersNotReady)));
+    client.set_ready(&guest, &42, &true);
+    assert_eq!(client.try_start_session(&guest, &42), Err(Ok(Error::NotSessionHost)));
+    assert_eq!(client.try_start_session(&host, &42), Err(Ok(Error::SeedsNotRevealed)));
+
+    reveal_seeds(&env, &client, 42);
+    client.start_session(&host, &42);
+    let session = client.get_session(&42).unwrap();
+    assert_eq!(session.status, SessionStatus::Countdown);
+    assert_eq!(client.get_session_players(&42), vec![&env, host.clone(), guest.clone()]);
+    assert_eq!(hub_started(&env, &client), None);
+
+    // The roster is locked once started
+    assert_eq!(
+        client.try_join_session(&latecomer, &42, &seed_commit(&env, &latecomer)),
+        Err(Ok(Error::SessionNotWaiting))
+    );
+    assert_eq!(
+        client.try_leave_session(&guest, &42),
+        Err(Ok(Error::SessionNotWaiting))
+    );
+
+    for trial in 1..=3 {
+        complete_session_trial(&env, &client, &guest, Some(42), trial);
+    }
+    assert_eq!(hub_started(&env, &client), Some((42, host.clone(), guest.clone())));
+    assert_eq!(hub_ended(&env, &client), Some((42, false)));
+    let session = client.get_session(&42).unwrap();
+    assert_eq!(session.status, SessionStatus::Finished);
+    assert_eq!(session.winner, Some(guest.clone()));
+    assert_eq!(client.get_player_session(&guest), None);
+}
+
+#[test]
+fn test_leave_session_hands_over_host() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+
+    client.create_session(&host, &1, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &1, &seed_commit(&env, &guest));
+    client.set_ready(&guest, &1, &true);
+
+    client.leave_session(&host, &1);
+    let session = client.get_session(&1).unwrap();
+    assert_eq!(session.host, guest);
+    assert_eq!(session.players, vec![&env, guest.clone()]);
+    assert_eq!(session.ready, vec![&env, guest.clone()]);
+    assert_eq!(client.get_player_session(&host), None);
+
+    client.leave_session(&guest, &1);
+    assert_eq!(client.get_session(&1), None);
+    assert_eq!(
+        client.try_join_session(&host, &1, &seed_commit(&env, &host)),
+        Err(Ok(Error::SessionNotFound))
+    );
+}
+
+#[test]
+fn test_concurrent_sessions_settle_independently() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players: std::vec::Vec<Address> = (0..4).map(|_| Address::generate(&env)).collect();
+
+    for (session_id, pair) in [(1, &players[0..2]), (2, &players[2..4])] {
+        client.create_session(&pair[0], &session_id, &0, &seed_commit(&env, &pair[0]));
+        client.join_session(&pair[1], &session_id, &seed_commit(&env, &pair[1]));
+        client.set_ready(&pair[1], &session_id, &true);
+        reveal_seeds(&env, &client, session_id);
+        client.start_session(&pair[0], &session_id);
+    }
+
+    // A player races in one session at a time
+    assert_eq!(
+        client.try_create_session(&players[0], &3, &0, &seed_commit(&env, &players[0])),
+        Err(Ok(Error::AlreadyJoined))
+    );
+
+    complete_session_trial(&env, &client, &players[1], Some(1), 1);
+    complete_session_trial(&env, &client, &players[2], Some(2), 1);
+    assert_eq!(client.get_session_progress(&1, &players[1]), 1);
+    assert_eq!(client.get_session_progress(&2, &players[2]), 1);
+    assert_eq!(client.get_session_progress(&1, &players[2]), 0);
+
+    for trial in 2..=3 {
+        complete_session_trial(&env, &client, &players[1], Some(1), trial);
+    }
+    assert_eq!(client.get_session(&1).unwrap().winner, Some(players[1].clone()));
+    assert_eq!(client.get_session(&2).unwrap().status, SessionStatus::Countdown);
+
+    // Session wins neither crown a King nor lock the round
+    assert_eq!(client.get_king(), None);
+    for trial in 2..=3 {
+        complete_session_trial(&env, &client, &players[2], Some(2), trial);
+    }
+    assert_eq!(client.get_session(&2).unwrap().winner, Some(players[2].clone()));
+    assert_eq!(hub_ended(&env, &client), Some((2, true)));
+    assert!(!client.is_locked());
+}
+
+#[test]
+fn test_session_submissions_limited_to_participants() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    client.create_session(&host, &7, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &7, &seed_commit(&env, &guest));
+
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let sigs = attest(&env, &client, &[(0, &backend_key())], 1, &host, &hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&host, &hash, &sigs, &1, &1, &EXPIRES_AT, &Some(7)),
+        Err(Ok(Error::SessionNotStarted))
+    );
+
+    client.set_ready(&guest, &7, &true);
+    reveal_seeds(&env, &client, 7);
+    client.start_session(&host, &7);
+
+    let sigs = attest(&env, &client, &[(0, &backend_key())], 1, &outsider, &hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&outsider, &hash, &sigs, &1, &1, &EXPIRES_AT, &Some(7)),
+        Err(Ok(Error::NotSessionPlayer))
+    );
+    assert_eq!(
+        client.try_submit_proof(&outsider, &hash, &sigs, &1, &1, &EXPIRES_AT, &Some(8)),
+        Err(Ok(Error::SessionNotFound))
+    );
+}
+
+#[test]
+fn test_session_seed_derived_from_reveals() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+
+    client.create_session(&host, &3, &0, &seed_commit(&env, &host));
+    assert_eq!(client.get_session_seed(&3), None);
+    assert_eq!(client.get_trial_variant(&3, &1, &4), None);
+
+    let players = start_session(&env, &client, 1, 2);
+    start_session(&env, &client, 2, 2);
+    let seed = client.get_session_seed(&1).unwrap();
+    assert_ne!(client.get_session_seed(&2), Some(seed.clone()));
+
+    // Hash of the session ID and every player's secret, in join order
+    let mut preimage = Bytes::from_array(&env, &1u32.to_be_bytes());
+    for player in &players {
+        preimage.extend_from_array(&seed_secret(&env, player).to_array());
+    }
+    assert_eq!(seed, BytesN::from(env.crypto().sha256(&preimage)));
+
+    // Recomputable from the published seed
+    let mut preimage = Bytes::from_array(&env, &seed.to_array());
+    preimage.extend_from_array(&5u32.to_be_bytes());
+    let digest = env.crypto().sha256(&preimage).to_array();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+    let expected = (u64::from_be_bytes(head) % 3) as u32;
+    assert_eq!(client.get_trial_variant(&1, &5, &3), Some(expected));
+    assert_eq!(client.get_trial_variant(&1, &5, &0), None);
+}
+
+#[test]
+fn test_seed_reveal_locks_lobby() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+
+    client.create_session(&host, &4, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &4, &seed_commit(&env, &guest));
+    assert_eq!(
+        client.try_reveal_seed(&host, &4, &seed_secret(&env, &host)),
+        Err(Ok(Error::PlayersNotReady))
+    );
+    client.set_ready(&guest, &4, &true);
+
+    // Only the committed secret is accepted, once
+    assert_eq!(
+        client.try_reveal_seed(&host, &4, &seed_secret(&env, &guest)),
+        Err(Ok(Error::InvalidSeedReveal))
+    );
+    client.reveal_seed(&host, &4, &seed_secret(&env, &host));
+    assert_eq!(
+        client.try_reveal_seed(&host, &4, &seed_secret(&env, &host)),
+        Err(Ok(Error::InvalidSeedReveal))
+    );
+
+    // Nobody can join, leave or change readiness once reveals started
+    let latecomer = Address::generate(&env);
+    assert_eq!(
+        client.try_join_session(&latecomer, &4, &seed_commit(&env, &latecomer)),
+        Err(Ok(Error::SeedRevealStarted))
+    );
+    assert_eq!(client.try_leave_session(&guest, &4), Err(Ok(Error::SeedRevealStarted)));
+    assert_eq!(
+        client.try_set_ready(&guest, &4, &false),
+        Err(Ok(Error::SeedRevealStarted))
+    );
+    assert_eq!(client.try_start_session(&host, &4), Err(Ok(Error::SeedsNotRevealed)));
+    assert_eq!(client.try_drop_unrevealed(&4), Err(Ok(Error::RevealWindowOpen)));
+
+    client.reveal_seed(&guest, &4, &seed_secret(&env, &guest));
+    client.start_session(&host, &4);
+    assert!(client.get_session_seed(&4).is_some());
+}
+
+#[test]
+fn test_drop_unrevealed_players() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&env)).collect();
+
+    client.create_session(&players[0], &5, &0, &seed_commit(&env, &players[0]));
+    for player in &players[1..] {
+        client.join_session(player, &5, &seed_commit(&env, player));
+        client.set_ready(player, &5, &true);
+    }
+
+    // The host withholds their secret after seeing the others
+    for player in &players[1..] {
+        client.reveal_seed(player, &5, &seed_secret(&env, player));
+    }
+    env.ledger().set_timestamp(301);
+    assert_eq!(client.drop_unrevealed(&5), vec![&env, players[0].clone()]);
+
+    let session = client.get_session(&5).unwrap();
+    assert_eq!(session.host, players[1]);
+    assert_eq!(session.players, vec![&env, players[1].clone(), players[2].clone()]);
+    assert_eq!(client.get_player_session(&players[0]), None);
+
+    client.start_session(&players[1], &5);
+    assert_eq!(client.get_session(&5).unwrap().status, SessionStatus::Countdown);
+}
+
+#[test]
+fn test_any_player_starts_after_reveal_window() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+
+    client.create_session(&host, &5, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &5, &seed_commit(&env, &guest));
+    client.set_ready(&guest, &5, &true);
+    reveal_seeds(&env, &client, 5);
+
+    // Everyone revealed, but the host never starts
+    assert_eq!(client.try_start_session(&guest, &5), Err(Ok(Error::NotSessionHost)));
+    env.ledger().set_timestamp(301);
+    assert_eq!(
+        client.try_start_session(&Address::generate(&env), &5),
+        Err(Ok(Error::NotSessionPlayer))
+    );
+
+    client.start_session(&guest, &5);
+    assert_eq!(client.get_session(&5).unwrap().status, SessionStatus::Countdown);
+}
+
+#[test]
+fn test_session_results_record_finish_times() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+
+    client.create_session(&host, &7, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &7, &seed_commit(&env, &guest));
+    client.set_ready(&guest, &7, &true);
+    reveal_seeds(&env, &client, 7);
+    client.start_session(&host, &7);
+
+    env.ledger().set_timestamp(100);
+    for trial in 1..=3 {
+        complete_session_trial(&env, &client, &host, Some(7), trial);
+    }
+    complete_session_trial(&env, &client, &guest, Some(7), 1);
+    assert_eq!(client.get_session(&7).unwrap().winner, Some(host.clone()));
+
+    // The runner-up can still finish after the session is won
+    env.ledger().set_timestamp(160);
+    for trial in 2..=3 {
+        complete_session_trial(&env, &client, &guest, Some(7), trial);
+    }
+    assert_eq!(client.get_session(&7).unwrap().winner, Some(host.clone()));
+
+    let solution_hash = BytesN::from_array(&env, &[4u8; 32]);
+    let signatures = attest(&env, &client, &[(0, &backend_key())], 4, &host, &solution_hash, 4);
+    assert_eq!(
+        client.try_submit_proof(&host, &solution_hash, &signatures, &4, &4, &EXPIRES_AT, &Some(7)),
+        Err(Ok(Error::AlreadyFinished))
+    );
+
+    assert_eq!(
+        client.get_session_results(&7),
+        vec![
+            &env,
+            SessionResult {
+                player: host,
+                progress: 3,
+                last_trial_at: Some(100),
+                finished_at: Some(100),
+                forfeited: false,
+                points: 0,
+            },
+            SessionResult {
+                player: guest,
+                progress: 3,
+                last_trial_at: Some(160),
+                finished_at: Some(160),
+                forfeited: false,
+                points: 0,
+            },
+        ]
+    );
+    assert_eq!(client.try_get_session_results(&8), Err(Ok(Error::SessionNotFound)));
+}
+
+#[test]
+fn test_forfeits_hand_session_to_last_player() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 5, 3);
+
+    assert_eq!(client.try_forfeit(&6, &players[0]), Err(Ok(Error::SessionNotFound)));
+    client.forfeit(&5, &players[0]);
+    assert_eq!(client.try_forfeit(&5, &players[0]), Err(Ok(Error::AlreadyForfeited)));
+    assert_eq!(client.get_player_session(&players[0]), None);
+
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let sigs = attest(&env, &client, &[(0, &backend_key())], 1, &players[0], &hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&players[0], &hash, &sigs, &1, &1, &EXPIRES_AT, &Some(5)),
+        Err(Ok(Error::AlreadyForfeited))
+    );
+
+    complete_session_trial(&env, &client, &players[1], Some(5), 1);
+    client.forfeit(&5, &players[1]);
+
+    let session = client.get_session(&5).unwrap();
+    assert_eq!(session.status, SessionStatus::Finished);
+    assert_eq!(session.winner, Some(players[2].clone()));
+    assert!(client.get_session_results(&5).get(1).unwrap().forfeited);
+    assert_eq!(client.try_forfeit(&5, &players[2]), Err(Ok(Error::SessionOver)));
+}
+
+#[test]
+fn test_expire_session_settles_best_progress() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    client.set_session_duration(&admin, &600);
+    let players = start_session(&env, &client, 9, 3);
+    assert_eq!(client.get_session(&9).unwrap().deadline, Some(600));
+
+    // Players 2 and 3 tie on progress; player 3 got there first
+    env.ledger().set_timestamp(100);
+    complete_session_trial(&env, &client, &players[2], Some(9), 1);
+    env.ledger().set_timestamp(200);
+    complete_session_trial(&env, &client, &players[1], Some(9), 1);
+
+    // Player 1 walks away; the others stay, so the session runs on
+    env.ledger().set_timestamp(300);
+    complete_session_trial(&env, &client, &players[0], Some(9), 1);
+    complete_session_trial(&env, &client, &players[0], Some(9), 2);
+    client.forfeit(&9, &players[0]);
+
+    assert_eq!(client.try_expire_session(&9), Err(Ok(Error::SessionNotExpired)));
+
+    env.ledger().set_timestamp(601);
+    let hash = BytesN::from_array(&env, &[2u8; 32]);
+    let sigs = attest(&env, &client, &[(0, &backend_key())], 2, &players[1], &hash, 2);
+    assert_eq!(
+        client.try_submit_proof(&players[1], &hash, &sigs, &2, &2, &EXPIRES_AT, &Some(9)),
+        Err(Ok(Error::SessionExpired))
+    );
+
+    assert_eq!(client.expire_session(&9), Some(players[2].clone()));
+    assert_eq!(client.get_session(&9).unwrap().winner, Some(players[2].clone()));
+    assert_eq!(client.get_player_session(&players[1]), None);
+    assert_eq!(client.try_expire_session(&9), Err(Ok(Error::SessionOver)));
+}
+
+#[test]
+fn test_expired_session_reports_to_hub() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 3, 2);
+
+    env.ledger().set_timestamp(10);
+    complete_session_trial(&env, &client, &players[1], Some(3), 1);
+
+    env.ledger().set_timestamp(client.get_session_duration() + 1);
+    client.expire_session(&3);
+    assert_eq!(hub_ended(&env, &client), Some((3, false)));
+}
+
+#[test]
+fn test_expire_session_without_progress_is_abandoned() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 3, 2);
+
+    env.ledger().set_timestamp(client.get_session_duration() + 1);
+    assert_eq!(client.expire_session(&3), None);
+
+    // Nobody wins, least of all the host, and the hub never saw the game
+    let session = client.get_session(&3).unwrap();
+    assert_eq!(session.status, SessionStatus::Abandoned);
+    assert_eq!(session.winner, None);
+    assert_eq!(hub_started(&env, &client), None);
+    assert_eq!(hub_ended(&env, &client), None);
+    assert_eq!(client.get_pending_hub_report(&3), None);
+
+    assert_eq!(client.get_player_session(&players[0]), None);
+    assert_eq!(client.try_expire_session(&3), Err(Ok(Error::SessionOver)));
+    assert_eq!(client.try_forfeit(&3, &players[1]), Err(Ok(Error::SessionOver)));
+}
+
+#[test]
+fn test_failed_hub_end_is_retried() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 4, 2);
+
+    // The winning submission goes through while the hub is down
+    set_hub_down(&env, &client, true);
+    for trial in 1..=3 {
+        complete_session_trial(&env, &client, &players[0], Some(4), trial);
+    }
+    assert_eq!(client.get_session(&4).unwrap().winner, Some(players[0].clone()));
+    assert_eq!(
+        client.get_pending_hub_report(&4),
+        Some(PendingHubReport { start_pending: true, player1_won: Some(true) })
+    );
+
+    assert_eq!(client.try_retry_hub_report(&4), Err(Ok(Error::HubUnavailable)));
+
+    // The start is delivered before the end
+    set_hub_down(&env, &client, false);
+    client.retry_hub_report(&4);
+    assert_eq!(hub_started(&env, &client), Some((4, players[0].clone(), players[1].clone())));
+    assert_eq!(hub_ended(&env, &client), Some((4, true)));
+    assert_eq!(client.get_pending_hub_report(&4), None);
+    assert_eq!(client.try_retry_hub_report(&4), Err(Ok(Error::NoPendingHubReport)));
+}
+
+#[test]
+fn test_hub_hears_of_sessions_once_they_end() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    // Starting does not need the hub
+    set_hub_down(&env, &client, true);
+    let players = start_session(&env, &client, 4, 2);
+    assert_eq!(client.get_session(&4).unwrap().status, SessionStatus::Countdown);
+    assert_eq!(client.get_pending_hub_report(&4), None);
+
+    set_hub_down(&env, &client, false);
+    assert_eq!(hub_started(&env, &client), None);
+    client.forfeit(&4, &players[0]);
+    assert_eq!(hub_started(&env, &client), Some((4, players[0].clone(), players[1].clone())));
+    assert_eq!(hub_ended(&env, &client), Some((4, false)));
+    assert_eq!(client.get_pending_hub_report(&4), None);
+}
+
+#[test]
+fn test_large_sessions_report_winner_against_runner_up() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 8, 4);
+
+    // Nothing is reported until the pair is known
+    assert_eq!(hub_started(&env, &client), None);
+
+    complete_session_trial(&env, &client, &players[1], Some(8), 1);
+    env.ledger().set_timestamp(50);
+    complete_session_trial(&env, &client, &players[2], Some(8), 1);
+    complete_session_trial(&env, &client, &players[2], Some(8), 2);
+    env.ledger().set_timestamp(60);
+    complete_session_trial(&env, &client, &players[0], Some(8), 1);
+    complete_session_trial(&env, &client, &players[0], Some(8), 2);
+    for trial in 1..=3 {
+        complete_session_trial(&env, &client, &players[3], Some(8), trial);
+    }
+
+    // Players 1 and 3 tie on progress; player 3 got there first
+    let pair = vec![&env, players[3].clone(), players[2].clone()];
+    assert_eq!(client.get_session(&8).unwrap().hub_pair, pair);
+    assert_eq!(hub_started(&env, &client), Some((8, players[3].clone(), players[2].clone())));
+    assert_eq!(hub_ended(&env, &client), Some((8, true)));
+}
+
+#[test]
+fn test_forfeited_runner_up_is_still_reported() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let players = start_session(&env, &client, 5, 3);
+
+    complete_session_trial(&env, &client, &players[0], Some(5), 1);
+    client.forfeit(&5, &players[0]);
+    client.forfeit(&5, &players[1]);
+
+    assert_eq!(
+        hub_started(&env, &client),
+        Some((5, players[2].clone(), players[0].clone()))
+    );
+    assert_eq!(hub_ended(&env, &client), Some((5, true)));
+}
+
+#[test]
+fn test_session_points_from_wagers_and_defaults() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let host = Address::generate(&env);
+    let guest = Address::generate(&env);
+
+    assert_eq!(client.try_set_default_points(&admin, &-1), Err(Ok(Error::InvalidPoints)));
+    client.set_default_points(&admin, &25);
+
+    client.create_session(&host, &3, &0, &seed_commit(&env, &host));
+    client.join_session(&guest, &3, &seed_commit(&env, &guest));
+    client.set_ready(&guest, &3, &true);
+    assert_eq!(
+        client.try_declare_points(&host, &3, &-5),
+        Err(Ok(Error::InvalidPoints))
+    );
+    client.declare_points(&host, &3, &100);
+    assert_eq!(client.get_session_points(&3, &guest), 25);
+
+    reveal_seeds(&env, &client, 3);
+    client.start_session(&host, &3);
+
+    // Amounts are fixed once the session started
+    client.set_default_points(&admin, &40);
+    assert_eq!(client.get_session_points(&3, &guest), 25);
+    assert_eq!(
+        client.try_declare_points(&guest, &3, &10),
+        Err(Ok(Error::SessionNotWaiting))
+    );
+
+    client.forfeit(&3, &guest);
+    assert_eq!(hub_points(&env, &client), Some((100, 25)));
+    let results = client.get_session_results(&3);
+    assert_eq!(results.get(0).unwrap().points, 100);
+    assert_eq!(results.get(1).unwrap().points, 25);
+}
+
+/// Register a wager token, configure it with `fee_bps` and fund `players`
+fn setup_wagers<'a>(
+    env: &Env,
+    client: &ThroneClient,
+    admin: &Address,
+    fee_bps: u32,
+    players: &[Address],
+) -> TokenClient<'a> {
+    let issuer = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    for player in players {
+        StellarAssetClient::new(env, &token).mint(player, &1_000);
+    }
+    client.set_wager_config(admin, &token, &fee_bps);
+    TokenClient::new(env, &token)
+}
+
+#[test]
+fn test_wager_pot_paid_to_winner_minus_fee() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let players: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&env)).collect();
+    let token = setup_wagers(&env, &client, &admin, 500, &players);
+
+    client.create_session(&players[0], &6, &100, &seed_commit(&env, &players[0]));
+    for player in &players[1..] {
+        client.join_session(player, &6, &seed_commit(&env, player));
+        client.set_ready(player, &6, &true);
+    }
+    reveal_seeds(&env, &client, 6);
+    client.start_session(&players[0], &6);
+    assert_eq!(token.balance(&client.address), 300);
+    assert_eq!(client.get_session(&6).unwrap().pot, 300);
+
+    // Raising the fee later does not touch running sessions
+    client.set_wager_config(&admin, &token.address, &10_000);
+
+    for trial in 1..=3 {
+        complete_session_trial(&env, &client, &players[1], Some(6), trial);
+    }
+
+    // 5% of the 300 pot goes to the admin
+    assert_eq!(token.balance(&players[1]), 1_185);
+    assert_eq!(token.balance(&admin), 15);
+    assert_eq!(token.balance(&players[0]), 900);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_session(&6).unwrap().pot, 0);
+}
+
+#[test]
+fn test_wager_refunds() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let players: std::vec::Vec<Address> = (0..2).map(|_| Address::generate(&env)).collect();
+
+    assert_eq!(
+        client.try_create_session(&players[0], &6, &100, &seed_commit(&env, &players[0])),
+        Err(Ok(Error::WagersNotConfigured))
+    );
+    let token = setup_wagers(&env, &client, &admin, 500, &players);
+    assert_eq!(
+        client.try_set_wager_config(&admin, &token.address, &10_001),
+        Err(Ok(Error::InvalidFee))
+    );
+    assert_eq!(
+        client.try_create_session(&players[0], &6, &-1, &seed_commit(&env, &players[0])),
+        Err(Ok(Error::InvalidStake))
+    );
+
+    // Leaving the lobby returns the stake
+    client.create_session(&players[0], &6, &100, &seed_commit(&env, &players[0]));
+    client.join_session(&players[1], &6, &seed_commit(&env, &players[1]));
+    client.leave_session(&players[1], &6);
+    assert_eq!(token.balance(&players[1]), 1_000);
+
+    // A session that expires without progress refunds everyone
+    client.join_session(&players[1], &6, &seed_commit(&env, &players[1]));
+    client.set_ready(&players[1], &6, &true);
+    reveal_seeds(&env, &client, 6);
+    client.start_session(&players[0], &6);
+    env.ledger().set_timestamp(client.get_session_duration() + 1);
+    assert_eq!(client.expire_session(&6), None);
+
+    assert_eq!(token.balance(&players[0]), 1_000);
+    assert_eq!(token.balance(&players[1]), 1_000);
+    assert_eq!(token.balance(&admin), 0);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_unrevealed_stake_goes_to_revealer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let players: std::vec::Vec<Address> = (0..2).map(|_| Address::generate(&env)).collect();
+    let token = setup_wagers(&env, &client, &admin, 500, &players);
+
+    client.create_session(&players[0], &6, &100, &seed_commit(&env, &players[0]));
+    client.join_session(&players[1], &6, &seed_commit(&env, &players[1]));
+    client.set_ready(&players[1], &6, &true);
+    client.reveal_seed(&players[1], &6, &seed_secret(&env, &players[1]));
+
+    // Too few players remain, so the session is abandoned
+    env.ledger().set_timestamp(301);
+    client.drop_unrevealed(&6);
+    assert_eq!(client.get_session(&6).unwrap().status, SessionStatus::Abandoned);
+    assert_eq!(client.get_player_session(&players[1]), None);
+
+    assert_eq!(token.balance(&players[0]), 900);
+    assert_eq!(token.balance(&players[1]), 1_100);
+    assert_eq!(token.balance(&client.address), 0);
+
+    // Nothing can be played in an abandoned session
+    let solution_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let signatures =
+        attest(&env, &client, &[(0, &backend_key())], 1, &players[1], &solution_hash, 1);
+    assert_eq!(
+        client.try_submit_proof(
+            &players[1],
+            &solution_hash,
+            &signatures,
+            &1,
+            &1,
+            &EXPIRES_AT,
+            &Some(6)
+        ),
+        Err(Ok(Error::SessionOver))
+    );
+}
+
+// ============================================================================
+// Backend Keys
+// ============================================================================
+
+#[test]
+fn test_backend_key_rotation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    let new_key = SigningKey::from_bytes(&[8u8; 32]);
+    let new_pubkey = BytesN::from_array(&env, &new_key.verifying_key().to_bytes());
+    let current_ledger = env.ledger().sequence();
+
+    // Scheduled for the next ledger, expires 100 ledgers later
+    let key_id = client.add_backend_key(
+        &admin,
+        &new_pubkey,
+        &(current_ledger + 1),
+        &Some(current_ledger + 101),
+    );
+    assert_eq!(key_id, 1);
+    assert_eq!(client.get_backend_key_count(), 2);
+
+    let solution_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let signatures = attest(&env, &client, &[(key_id, &new_key)], 1, &player, &solution_hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &signatures, &1, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::InactiveBackendKey))
+    );
+
+    // The only valid key can't be revoked before its successor is active
+    assert_eq!(
+        client.try_revoke_backend_key(&admin, &0),
+        Err(Ok(Error::BelowThreshold))
+    );
+
+    // New key active, old key revoked
+    env.ledger().set_sequence_number(current_ledger + 1);
+    client.revoke_backend_key(&admin, &0);
+
+    let old_key = [(0, &backend_key())];
+    let old = attest(&env, &client, &old_key, 1, &player, &solution_hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &old, &1, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::InactiveBackendKey))
+    );
+    client.submit_proof(&player, &solution_hash, &signatures, &1, &1, &EXPIRES_AT, &None);
+    assert_eq!(client.get_progress(&player), 1);
+
+    // Expired
+    env.ledger().set_sequence_number(current_ledger + 101);
+    let solution_hash = BytesN::from_array(&env, &[2u8; 32]);
+    let signatures = attest(&env, &client, &[(key_id, &new_key)], 2, &player, &solution_hash, 2);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &signatures, &2, &2, &EXPIRES_AT, &None),
+        Err(Ok(Error::InactiveBackendKey))
+    );
+
+    let unknown = attest(&env, &client, &[(7, &new_key)], 2, &player, &solution_hash, 2);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &unknown, &2, &2, &EXPIRES_AT, &None),
+        Err(Ok(Error::UnknownBackendKey))
+    );
+}
+
+#[test]
+fn test_threshold_attestations() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    let second_key = SigningKey::from_bytes(&[8u8; 32]);
+    let second_pubkey = BytesN::from_array(&env, &second_key.verifying_key().to_bytes());
+    client.add_backend_key(&admin, &second_pubkey, &0, &None);
+
+    assert_eq!(
+        client.try_set_attestation_threshold(&admin, &3),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    client.set_attestation_threshold(&admin, &2);
+
+    let solution_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let first_key = backend_key();
+
+    let single = attest(&env, &client, &[(0, &first_key)], 1, &player, &solution_hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &single, &1, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::InsufficientSignatures))
+    );
+
+    let same_key_twice = [(0, &first_key), (0, &first_key)];
+    let duplicated = attest(&env, &client, &same_key_twice, 1, &player, &solution_hash, 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &duplicated, &1, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::DuplicateSigner))
+    );
+
+    let both_keys = [(0, &first_key), (1, &second_key)];
+    let both = attest(&env, &client, &both_keys, 1, &player, &solution_hash, 1);
+    client.submit_proof(&player, &solution_hash, &both, &1, &1, &EXPIRES_AT, &None);
+    assert_eq!(client.get_progress(&player), 1);
+}
+
+#[test]
+fn test_threshold_counts_valid_keys_only() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let current_ledger = env.ledger().sequence();
+
+    let pubkey = |seed: u8| {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        BytesN::from_array(&env, &key.verifying_key().to_bytes())
+    };
+    let revoked = client.add_backend_key(&admin, &pubkey(8), &0, &None);
+    client.revoke_backend_key(&admin, &revoked);
+    client.add_backend_key(&admin, &pubkey(9), &0, &Some(current_ledger + 10));
+    client.add_backend_key(&admin, &pubkey(10), &(current_ledger + 5), &None);
+
+    // Key 0 and the expiring key are valid now; the revoked and the
+    // scheduled key don't count
+    assert_eq!(client.get_backend_key_count(), 4);
+    assert_eq!(
+        client.try_set_attestation_threshold(&admin, &3),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    client.set_attestation_threshold(&admin, &2);
+
+    // Revoking either valid key would leave too few
+    assert_eq!(
+        client.try_revoke_backend_key(&admin, &0),
+        Err(Ok(Error::BelowThreshold))
+    );
+
+    // Revoking an already invalid key is fine
+    client.revoke_backend_key(&admin, &revoked);
+
+    // Once the scheduled key is active, one of the others can go
+    env.ledger().set_sequence_number(current_ledger + 5);
+    client.revoke_backend_key(&admin, &0);
+    assert_eq!(
+        client.try_revoke_backend_key(&admin, &2),
+        Err(Ok(Error::BelowThreshold))
+    );
+}
+
+#[test]
+fn test_attestation_bound_to_contract_round_and_expiry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let (other_client, _) = setup(&env);
+    let player = Address::generate(&env);
+    let solution_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let signers = [(0, &backend_key())];
+
+    // Signed for a different deployment
+    let foreign = attest(&env, &other_client, &signers, 1, &player, &solution_hash, 1);
+    assert!(client
+        .try_submit_proof(&player, &solution_hash, &foreign, &1, &1, &EXPIRES_AT, &None)
+        .is_err());
+
+    // Signed for a previous game round
+    let stale = attest(&env, &client, &signers, 1, &player, &solution_hash, 1);
+    client.start_new_round(&admin);
+    assert!(client
+        .try_submit_proof(&player, &solution_hash, &stale, &1, &1, &EXPIRES_AT, &None)
+        .is_err());
+
+    // Past its expiry
+    let signatures = attest(&env, &client, &signers, 1, &player, &solution_hash, 1);
+    env.ledger().set_timestamp(EXPIRES_AT + 1);
+    assert_eq!(
+        client.try_submit_proof(&player, &solution_hash, &signatures, &1, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::AttestationExpired))
+    );
+    assert_eq!(client.get_progress(&player), 0);
+}
+
+#[test]
+fn test_failed_trial_locks_player_out() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let player = Address::generate(&env);
+    env.ledger().set_timestamp(100);
+
+    // A completion attestation does not pass for a failure
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let completion = attest(&env, &client, &[(0, &backend_key())], 1, &player, &hash, 1);
+    assert!(client
+        .try_record_failure(&player, &1, &completion, &1, &EXPIRES_AT)
+        .is_err());
+
+    let failure = attest_failure(&env, &client, 1, &player, 1);
+    assert_eq!(client.record_failure(&player, &1, &failure, &1, &EXPIRES_AT), 220);
+    assert_eq!(client.check_lockout(&player), (true, 120));
+    assert_eq!(
+        client.try_record_failure(&player, &1, &failure, &1, &EXPIRES_AT),
+        Err(Ok(Error::InvalidNonce))
+    );
+
+    let sigs = attest(&env, &client, &[(0, &backend_key())], 1, &player, &hash, 2);
+    assert_eq!(
+        client.try_submit_proof(&player, &hash, &sigs, &2, &1, &EXPIRES_AT, &None),
+        Err(Ok(Error::LockedOut))
+    );
+
+    env.ledger().set_timestamp(220);
+    assert_eq!(client.check_lockout(&player), (false, 0));
+    client.submit_proof(&player, &hash, &sigs, &2, &1, &EXPIRES_AT, &None);
+    assert_eq!(client.get_progress(&player), 1);
+}
+
+#[test]
+fn test_escalating_lockouts() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let player = Address::generate(&env);
+
+    assert_eq!(
+        client.try_set_lockout_config(&admin, &60, &true, &30),
+        Err(Ok(Error::InvalidLockout))
+    );
+    client.set_lockout_config(&admin, &60, &true, &200);
+
+    // 60s, then doubled, then capped
+    for (nonce, lockout) in [(1, 60), (2, 120), (3, 200)] {
+        let failure = attest_failure(&env, &client, 2, &player, nonce);
+        client.record_failure(&player, &2, &failure, &nonce, &EXPIRES_AT);
+        assert_eq!(client.check_lockout(&player), (true, lockout));
+    }
+    assert_eq!(client.get_failure_record(&player).unwrap().failures, 3);
+
+    // Escalation starts over in a new round
+    client.start_new_round(&admin);
+    let failure = attest_failure(&env, &client, 2, &player, 4);
+    client.record_failure(&player, &2, &failure, &4, &EXPIRES_AT);
+    assert_eq!(client.check_lockout(&player), (true, 60));
+}
+
+// ============================================================================
+// Upgrade
+// ============================================================================
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_upgrade(&stranger, &BytesN::from_array(&env, &[0u8; 32])),
+        Err(Ok(Error::NotAdmin))
+    );
+}
@@ -43,6 +43,9 @@ interface MultiplayerContextType {
 
 const MultiplayerContext = createContext<MultiplayerContextType | null>(null);
 
+// On-chain session ID of a backend room (first 32 bits of the room ID)
+const sessionIdForRoom = (roomId: string) => parseInt(roomId.slice(0, 8), 16);
+
 // ============================================================================
 // Provider Component
 // ============================================================================
@@ -67,8 +70,12 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
   const [isHost, setIsHost] = useState(false);
   const [countdown, setCountdown] = useState<number | null>(null);
   
-  // Track if we've already called startMultiplayerSession for this game
+  // Track if we've already revealed our seed / started the on-chain session
+  const seedRevealedRef = useRef<string | null>(null);
   const sessionStartedRef = useRef<string | null>(null);
+  // Calls in flight; failed calls are retried on the next poll
+  const revealInFlightRef = useRef(false);
+  const startInFlightRef = useRef(false);
 
   /**
    * Create a new room (becomes host)
@@ -83,6 +90,12 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
       const result = await multiplayerService.createRoom(wallet, maxPlayers, totalRounds);
       
       console.log("✅ [Context] Room created:", result.joinCode);
+
+      // Open the matching on-chain session (host is ready at once)
+      const session = await throneContractService.createSession(sessionIdForRoom(result.roomId));
+      if (!session.success) {
+        throw new Error(session.error || "Failed to create on-chain session");
+      }
       
       // Immediately fetch the room state
       const roomState = await multiplayerService.getRoomState(result.roomId);
@@ -117,6 +130,17 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
       const result = await multiplayerService.joinRoom(roomId, wallet);
       
       console.log("✅ [Context] Joined room");
+
+      // Join the matching on-chain session; each player signs their own join
+      const sessionId = sessionIdForRoom(roomId);
+      const joined = await throneContractService.joinSession(sessionId);
+      if (!joined.success) {
+        throw new Error(joined.error || "Failed to join on-chain session");
+      }
+      const ready = await throneContractService.setReady(sessionId, true);
+      if (!ready.success) {
+        throw new Error(ready.error || "Failed to mark ready on-chain");
+      }
       
       setCurrentRoom(result.roomState);
       sessionStorage.setItem('multiplayer-room', JSON.stringify(result.roomState)); // Persist
@@ -172,40 +196,6 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
       const wallet = walletService.getPublicKey();
       if (!wallet) throw new Error("Wallet not connected");
 
-      // CRITICAL: Call start_multiplayer_session on FIRST trial submission (host only)
-      // This delays wallet popup until player actually plays, not at game start
-      if (isHost && !sessionStartedRef.current && currentRoom) {
-        sessionStartedRef.current = currentRoom.roomId; // Mark immediately to prevent duplicates
-        
-        console.log("🎮 [Context] HOST: Starting multiplayer session on-chain (first trial submission)...");
-        
-        // Only proceed with contract call for 2-player games (Game Hub limitation)
-        if (currentRoom.players.length === 2) {
-          const player1 = currentRoom.players[0].wallet;
-          const player2 = currentRoom.players[1].wallet;
-          const sessionId = parseInt(currentRoom.roomId.slice(0, 8), 16);
-          
-          console.log("📊 [Context] Session params:", { sessionId, player1, player2 });
-          
-          // Fire and forget - don't block trial submission
-          throneContractService.startMultiplayerSession(sessionId, player1, player2)
-            .then(result => {
-              if (result.success) {
-                console.log("✅ [Context] Multiplayer session started on-chain!");
-                console.log("🏆 [Context] Game Hub notified via start_game()");
-                console.log(`🔗 [Context] TX: ${result.txHash}`);
-              } else {
-                console.error("❌ [Context] Failed to start session:", result.error);
-              }
-            })
-            .catch(error => {
-              console.error("❌ [Context] Error starting session:", error);
-            });
-        } else {
-          console.log("ℹ️  [Context] 3-4 player game: Skipping Game Hub integration (only supports 2 players)");
-        }
-      }
-
       console.log(`📝 [Context] Submitting solution for round ${roundId} to backend...`);
       
       // STEP 1: Submit to backend - generates ZK proof & verifies
@@ -222,7 +212,10 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
 
       // STEP 2: Submit attestation to contract on-chain
       console.log("🔗 [Context] Submitting to contract...");
-      const contractResult = await throneContractService.submitProof(result.attestation);
+      const contractResult = await throneContractService.submitProof(
+        result.attestation,
+        sessionIdForRoom(targetRoomId)
+      );
 
       if (!contractResult.success) {
         console.error("❌ [Context] Contract submission failed");
@@ -241,7 +234,7 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
       console.error("❌ [Context] Failed to submit solution:", error);
       throw error;
     }
-  }, [currentRoom, isHost]);
+  }, [currentRoom]);
 
   /**
    * Leave current room
@@ -253,7 +246,68 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
     setIsInRoom(false);
     setIsHost(false);
     setCountdown(null);
-    sessionStartedRef.current = null; // Reset session tracker
+    seedRevealedRef.current = null; // Reset session trackers
+    sessionStartedRef.current = null;
+  }, []);
+
+  /**
+   * Reveal our seed secret, unless the session already has it
+   */
+  const revealSessionSeed = useCallback(async (roomId: string) => {
+    if (seedRevealedRef.current === roomId || revealInFlightRef.current) return;
+    revealInFlightRef.current = true;
+
+    try {
+      const sessionId = sessionIdForRoom(roomId);
+      const session = await throneContractService.getSession(sessionId);
+      if (session?.revealed.includes(walletService.getPublicKey() ?? "")) {
+        seedRevealedRef.current = roomId;
+        return;
+      }
+
+      const result = await throneContractService.revealSeed(sessionId);
+      if (result.success) {
+        seedRevealedRef.current = roomId;
+      } else {
+        console.error("❌ [Context] Failed to reveal seed, retrying:", result.error);
+      }
+    } finally {
+      revealInFlightRef.current = false;
+    }
+  }, []);
+
+  /**
+   * HOST: start the on-chain session once every player revealed
+   */
+  const startOnChainSession = useCallback(async (roomId: string) => {
+    if (sessionStartedRef.current === roomId || startInFlightRef.current) return;
+    startInFlightRef.current = true;
+
+    try {
+      const sessionId = sessionIdForRoom(roomId);
+      const session = await throneContractService.getSession(sessionId);
+      if (!session) return;
+      if (session.status !== "Waiting") {
+        sessionStartedRef.current = roomId; // Already started
+        return;
+      }
+      if (session.revealed.length < session.players.length) {
+        console.log(
+          `⏳ [Context] Waiting for seed reveals (${session.revealed.length}/${session.players.length})`
+        );
+        return;
+      }
+
+      const result = await throneContractService.startSession(sessionId);
+      if (result.success) {
+        sessionStartedRef.current = roomId;
+        console.log("🏆 [Context] On-chain session started");
+      } else {
+        console.error("❌ [Context] Failed to start session, retrying:", result.error);
+      }
+    } finally {
+      startInFlightRef.current = false;
+    }
   }, []);
 
   /**
@@ -308,12 +362,26 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
           if (remaining === 0) {
             console.log("🎮 [Context] Game is starting NOW!");
           }
+
+          // Every player reveals their seed secret during the countdown
+          revealSessionSeed(newState.roomId);
         }
 
         // Trigger game start when countdown finishes
         if (newState.state === "IN_PROGRESS") {
           console.log("🎮 [Context] Game state is IN_PROGRESS, game active!");
           setCountdown(0);
+
+          // A reveal that failed during the countdown is retried here
+          revealSessionSeed(newState.roomId);
+
+          // HOST: start the on-chain session once everyone revealed
+          const isRoomHost = newState.players.some(
+            (player) => player.isHost && player.wallet === walletService.getPublicKey()
+          );
+          if (isRoomHost) {
+            startOnChainSession(newState.roomId);
+          }
         }
       },
       2000 // Poll every 2 seconds
@@ -323,7 +391,7 @@ export function MultiplayerProvider({ children }: { children: ReactNode }) {
       console.log("📡 [Context] Stopped room state polling");
       stopPolling();
     };
-  }, [currentRoom?.roomId]);
+  }, [currentRoom?.roomId, revealSessionSeed, startOnChainSession]);
 
   /**
    * Countdown timer effect
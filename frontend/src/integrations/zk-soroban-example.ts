@@ -39,6 +39,7 @@ interface PlayerProgress {
   player: string;
   round_id: number;
   trials_completed: number;
+  score: number;
   last_trial_timestamp: number;
   is_king: boolean;
 }
@@ -388,6 +389,7 @@ function decodePlayerProgress(scVal: StellarSdk.xdr.ScVal): PlayerProgress {
     player: 'GXXXX...',
     round_id: 1,
     trials_completed: 3,
+    score: 3,
     last_trial_timestamp: Date.now(),
     is_king: false,
   };
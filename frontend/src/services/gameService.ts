@@ -69,7 +69,7 @@ class GameService {
 
       const attestation = backendResponse.attestation;
       console.log("✅ Backend attestation received");
-      console.log("   • Signature:", attestation.signature.substring(0, 20) + "...");
+      console.log("   • Signatures:", attestation.signatures.length);
       console.log("   • Solution Hash:", attestation.solutionHash.substring(0, 20) + "...");
       console.log("   • Nonce:", attestation.nonce);
 
@@ -2,6 +2,8 @@
 // MULTIPLAYER SERVICE - Connect to Backend Room System
 // ============================================================================
 
+import type { Attestation } from "./throneContractService";
+
 const BACKEND_URL = import.meta.env.VITE_BACKEND_URL || "http://localhost:3030";
 
 export interface Player {
@@ -178,13 +180,7 @@ class MultiplayerService {
     message: string;
     playerFinished: boolean;  // This player finished all rounds
     gameFinished: boolean;  // Game ended (first player finished - race mode!)
-    attestation: {
-      signature: string;
-      solutionHash: string;
-      nonce: number;
-      roundId: number;
-      player: string;
-    };
+    attestation: Attestation;
   }> {
     try {
       const response = await fetch(
@@ -11,6 +11,7 @@ import {
   Address,
   nativeToScVal,
   scValToNative,
+  xdr,
   BASE_FEE
 } from "@stellar/stellar-sdk";
 import { walletService } from "./walletService";
@@ -25,14 +26,36 @@ const CONFIG = {
     "https://soroban-testnet.stellar.org",
 };
 
-interface Attestation {
+interface AttestationSignature {
+  keyId: number; // Backend key_id registered in the contract
   signature: string; // base64
+}
+
+interface Attestation {
+  signatures: AttestationSignature[];
   solutionHash: string; // hex
   nonce: number;
-  roundId: number;
+  roundId: number; // Trial ID the attestation is signed for
+  gameRound: number; // Contract game round the attestation is signed for
+  expiresAt: number; // Ledger timestamp after which the attestation is void
   player: string;
 }
 
+interface ContractCallResult {
+  success: boolean;
+  txHash?: string;
+  error?: string;
+}
+
+interface SessionState {
+  status: string; // "Waiting" | "Countdown" | "Finished" | "Abandoned"
+  players: string[]; // In join order
+  revealed: string[]; // Players who revealed their seed secret
+}
+
+// Seed secrets are kept until revealed, so a reload can still reveal them
+const seedSecretKey = (sessionId: number) => `session-seed-${sessionId}`;
+
 class ThroneContractService {
   private server: SorobanRpc.Server;
   private contract: Contract;
@@ -45,12 +68,11 @@ class ThroneContractService {
   /**
    * Submit proof attestation to contract
    * This is the core function that connects backend → frontend → contract
+   *
+   * @param attestation - Backend attestation for one trial
+   * @param sessionId - On-chain session the trial counts towards (solo play if omitted)
    */
-  async submitProof(attestation: Attestation): Promise<{
-    success: boolean;
-    txHash?: string;
-    error?: string;
-  }> {
+  async submitProof(attestation: Attestation, sessionId?: number): Promise<ContractCallResult> {
     try {
       const publicKey = walletService.getPublicKey();
       if (!publicKey) {
@@ -60,14 +82,9 @@ class ThroneContractService {
       console.log("🔗 Submitting proof to contract...");
       console.log("📝 Attestation:", attestation);
 
-      // STEP 0: Get player's current on-chain progress
-      // The contract requires trial_round_id = progress + 1
-      const currentProgress = await this.getProgress(publicKey);
-      const expectedTrialRoundId = currentProgress + 1;
-      
-      console.log(`📊 Player on-chain progress: ${currentProgress}`);
-      console.log(`🎯 Expected trial_round_id: ${expectedTrialRoundId}`);
-      console.log(`📋 Attestation roundId: ${attestation.roundId} (game round, not used)`);
+      // Trials can be completed in any order; the attestation is signed
+      // for one trial ID, which is passed as trial_round_id
+      console.log(`🎯 Trial: ${attestation.roundId} (game round ${attestation.gameRound})`);
 
       // STEP 1: Load source account
       const sourceAccount = await this.server.getAccount(publicKey);
@@ -84,35 +101,42 @@ class ThroneContractService {
         throw new Error("Solution hash must be 32 bytes");
       }
 
-      // Convert base64 signature to BytesN<64>
-      const signatureBytes = Buffer.from(attestation.signature, "base64");
-      if (signatureBytes.length !== 64) {
-        throw new Error("Signature must be 64 bytes (Ed25519)");
-      }
+      // Convert each base64 signature to a (key_id, BytesN<64>) tuple
+      const signaturesScVal = xdr.ScVal.scvVec(
+        attestation.signatures.map(({ keyId, signature }) => {
+          const signatureBytes = Buffer.from(signature, "base64");
+          if (signatureBytes.length !== 64) {
+            throw new Error("Signature must be 64 bytes (Ed25519)");
+          }
+          return xdr.ScVal.scvVec([
+            nativeToScVal(keyId, { type: "u32" }),
+            nativeToScVal(signatureBytes, { type: "bytes" }),
+          ]);
+        })
+      );
 
       // STEP 3: Build contract call
       // Convert to fixed-length BytesN types using explicit maxLength
       const solutionHashScVal = nativeToScVal(solutionHashBytes, {
         type: "bytes",
       });
-      const signatureScVal = nativeToScVal(signatureBytes, {
-        type: "bytes",
-      });
-      
+
       console.log("📦 ScVal types:", {
         solutionHash: solutionHashScVal,
-        signature: signatureScVal,
+        signatures: signaturesScVal,
       });
-      
-      // Build contract call with trial_round_id parameter
-      // Use on-chain progress + 1, NOT the game roundId!
+
       const operation = this.contract.call(
         "submit_proof",
         playerAddress.toScVal(),
         solutionHashScVal,
-        signatureScVal,
+        signaturesScVal,
         nativeToScVal(attestation.nonce, { type: "u64" }),
-        nativeToScVal(expectedTrialRoundId, { type: "u32" })  // trial_round_id based on on-chain progress
+        nativeToScVal(attestation.roundId, { type: "u32" }),  // trial_round_id
+        nativeToScVal(attestation.expiresAt, { type: "u64" }),
+        sessionId === undefined
+          ? xdr.ScVal.scvVoid()
+          : nativeToScVal(sessionId, { type: "u32" })  // Option<u32> session_id
       );
 
       // STEP 4: Build transaction with higher fee for Soroban
@@ -366,61 +390,194 @@ class ThroneContractService {
   }
 
   /**
-   * Start multiplayer session - calls Game Hub's start_game()
-   * REQUIRED for hackathon compliance
-   * Called when countdown finishes and game begins (HOST ONLY)
-   * 
-   * NOTE: Game Hub only supports 2-player tracking
-   * For 3-4 player games, this should NOT be called (game works fine without it)
-   * The contract's end_game() is only called if this session is started
-   * 
+   * Get an on-chain session (null if it does not exist or the call fails)
+   */
+  async getSession(sessionId: number): Promise<SessionState | null> {
+    try {
+      const operation = this.contract.call(
+        "get_session",
+        nativeToScVal(sessionId, { type: "u32" })
+      );
+
+      const tempKeypair = Keypair.random();
+      const account = new Account(tempKeypair.publicKey(), "0");
+
+      const transaction = new TransactionBuilder(account, {
+        fee: BASE_FEE,
+        networkPassphrase: CONFIG.networkPassphrase,
+      })
+        .addOperation(operation)
+        .setTimeout(300)
+        .build();
+
+      const simulated = await this.server.simulateTransaction(transaction);
+
+      if (SorobanRpc.Api.isSimulationSuccess(simulated)) {
+        const result = simulated.result?.retval;
+        const session = result ? scValToNative(result) : null;
+        if (session) {
+          // Unit enum variants decode as [name]; maps as plain objects
+          return {
+            status: session.status[0],
+            players: session.players,
+            revealed: Object.keys(session.seed_reveals),
+          };
+        }
+      }
+
+      return null;
+    } catch (error) {
+      console.error("Failed to get session:", error);
+      return null;
+    }
+  }
+
+  /**
+   * Open an on-chain session lobby (host joins and is ready at once)
+   * Commits to a fresh seed secret, revealed later with revealSeed()
+   *
    * @param sessionId - Unique session identifier
-   * @param player1 - First player address (host)
-   * @param player2 - Second player address
+   * @param stake - Wager each player escrows (0 = none)
+   */
+  async createSession(sessionId: number, stake: number = 0): Promise<ContractCallResult> {
+    const host = walletService.getPublicKey();
+    if (!host) {
+      return { success: false, error: "Wallet not connected" };
+    }
+
+    const seedCommit = await this.newSeedCommit(sessionId);
+    return this.invokeSessionCall(
+      "create_session",
+      Address.fromString(host).toScVal(),
+      nativeToScVal(sessionId, { type: "u32" }),
+      nativeToScVal(stake, { type: "i128" }),
+      nativeToScVal(seedCommit, { type: "bytes" })
+    );
+  }
+
+  /**
+   * Join an on-chain session lobby, committing to a fresh seed secret
+   */
+  async joinSession(sessionId: number): Promise<ContractCallResult> {
+    const player = walletService.getPublicKey();
+    if (!player) {
+      return { success: false, error: "Wallet not connected" };
+    }
+
+    const seedCommit = await this.newSeedCommit(sessionId);
+    return this.invokeSessionCall(
+      "join_session",
+      Address.fromString(player).toScVal(),
+      nativeToScVal(sessionId, { type: "u32" }),
+      nativeToScVal(seedCommit, { type: "bytes" })
+    );
+  }
+
+  /**
+   * Mark the connected player as ready (or not) in an on-chain session
+   */
+  async setReady(sessionId: number, ready: boolean): Promise<ContractCallResult> {
+    const player = walletService.getPublicKey();
+    if (!player) {
+      return { success: false, error: "Wallet not connected" };
+    }
+
+    return this.invokeSessionCall(
+      "set_ready",
+      Address.fromString(player).toScVal(),
+      nativeToScVal(sessionId, { type: "u32" }),
+      nativeToScVal(ready, { type: "bool" })
+    );
+  }
+
+  /**
+   * Reveal the seed secret committed when creating or joining a session
+   * Every player reveals once all are ready; the session seed is derived
+   * from all reveals, so no player can pick the question variants.
+   * Players who do not reveal in time can be dropped and lose their stake.
+   */
+  async revealSeed(sessionId: number): Promise<ContractCallResult> {
+    const player = walletService.getPublicKey();
+    if (!player) {
+      return { success: false, error: "Wallet not connected" };
+    }
+
+    const secretHex = localStorage.getItem(seedSecretKey(sessionId));
+    if (!secretHex) {
+      return { success: false, error: "No seed secret stored for this session" };
+    }
+
+    const result = await this.invokeSessionCall(
+      "reveal_seed",
+      Address.fromString(player).toScVal(),
+      nativeToScVal(sessionId, { type: "u32" }),
+      nativeToScVal(Buffer.from(secretHex, "hex"), { type: "bytes" })
+    );
+    if (result.success) {
+      localStorage.removeItem(seedSecretKey(sessionId));
+    }
+    return result;
+  }
+
+  /**
+   * Start an on-chain session
+   * Host only (any player once the reveal window is over), once 2-4 players
+   * joined, are ready and revealed their seeds
+   *
+   * NOTE: The contract reports the session to the Game Hub (start_game and
+   * end_game) once it has a winner
+   */
+  async startSession(sessionId: number): Promise<ContractCallResult> {
+    const host = walletService.getPublicKey();
+    if (!host) {
+      return { success: false, error: "Wallet not connected" };
+    }
+
+    return this.invokeSessionCall(
+      "start_session",
+      Address.fromString(host).toScVal(),
+      nativeToScVal(sessionId, { type: "u32" })
+    );
+  }
+
+  /**
+   * Generate and store a seed secret for a session, returning its SHA256
+   */
+  private async newSeedCommit(sessionId: number): Promise<Buffer> {
+    const secret = crypto.getRandomValues(new Uint8Array(32));
+    localStorage.setItem(seedSecretKey(sessionId), Buffer.from(secret).toString("hex"));
+    return Buffer.from(await crypto.subtle.digest("SHA-256", secret));
+  }
+
+  /**
+   * Sign and submit a session call with the connected wallet, waiting for
+   * confirmation
    */
-  async startMultiplayerSession(
-    sessionId: number,
-    player1: string,
-    player2: string
-  ): Promise<{
-    success: boolean;
-    txHash?: string;
-    error?: string;
-  }> {
+  private async invokeSessionCall(
+    method: string,
+    ...args: xdr.ScVal[]
+  ): Promise<ContractCallResult> {
     try {
       const publicKey = walletService.getPublicKey();
       if (!publicKey) {
         throw new Error("Wallet not connected");
       }
 
-      console.log("🎮 Starting multiplayer session on contract...");
-      console.log("📊 Session:", { sessionId, player1, player2 });
+      console.log(`🎮 Calling ${method} on contract...`);
 
       // STEP 1: Load source account
       const sourceAccount = await this.server.getAccount(publicKey);
 
-      // STEP 2: Convert parameters to Soroban types
-      const player1Address = Address.fromString(player1);
-      const player2Address = Address.fromString(player2);
-
-      // STEP 3: Build contract call
-      const operation = this.contract.call(
-        "start_multiplayer_session",
-        nativeToScVal(sessionId, { type: "u32" }),
-        player1Address.toScVal(),
-        player2Address.toScVal()
-      );
-
-      // STEP 4: Build transaction
+      // STEP 2: Build transaction
       let transaction = new TransactionBuilder(sourceAccount, {
         fee: "10000000", // 1 XLM max fee for Soroban
         networkPassphrase: CONFIG.networkPassphrase,
       })
-        .addOperation(operation)
+        .addOperation(this.contract.call(method, ...args))
         .setTimeout(300)
         .build();
 
-      // STEP 5: Simulate to get auth
+      // STEP 3: Simulate to get auth
       console.log("🔍 Simulating transaction...");
       const simulated = await this.server.simulateTransaction(transaction);
 
@@ -429,13 +586,13 @@ class ThroneContractService {
         throw new Error(`Simulation failed: ${simulated.error}`);
       }
 
-      // STEP 6: Prepare transaction with auth
+      // STEP 4: Prepare transaction with auth
       transaction = SorobanRpc.assembleTransaction(
         transaction,
         simulated
       ).build();
 
-      // STEP 7: Sign with wallet
+      // STEP 5: Sign with wallet
       const signedXdr = await walletService.signTransaction(
         transaction.toXDR()
       );
@@ -444,8 +601,8 @@ class ThroneContractService {
         throw new Error("User rejected transaction");
       }
 
-      // STEP 8: Submit transaction
-      console.log("📤 Submitting start_multiplayer_session transaction...");
+      // STEP 6: Submit transaction
+      console.log(`📤 Submitting ${method} transaction...`);
       const signedTx = TransactionBuilder.fromXDR(
         signedXdr,
         CONFIG.networkPassphrase
@@ -472,8 +629,7 @@ class ThroneContractService {
         }
 
         if (getResponse.status === SorobanRpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log("✅ Multiplayer session started on-chain!");
-          console.log("🏆 Game Hub notified via start_game()");
+          console.log(`✅ ${method} confirmed on-chain!`);
           console.log(`🔗 TX: ${sendResponse.hash}`);
 
           return {
@@ -487,14 +643,14 @@ class ThroneContractService {
         throw new Error("Transaction submission failed");
       }
     } catch (error: any) {
-      console.error("❌ Failed to start multiplayer session:", error);
+      console.error(`❌ ${method} failed:`, error);
       return {
         success: false,
-        error: error.message || "Failed to start session",
+        error: error.message || `Failed to call ${method}`,
       };
     }
   }
 }
 
 export const throneContractService = new ThroneContractService();
-export type { Attestation };
+export type { Attestation, AttestationSignature, SessionState };
@@ -2,18 +2,14 @@
 // ZK BACKEND SERVICE
 // ============================================================================
 
+import type { Attestation } from "./throneContractService";
+
 const ZK_SERVER_URL =
   import.meta.env.VITE_ZK_SERVER_URL || "http://localhost:3030";
 
 interface BackendResponse {
   success: boolean;
-  attestation?: {
-    signature: string;
-    solutionHash: string;
-    nonce: number;
-    roundId: number;
-    player: string;
-  };
+  attestation?: Attestation;
   error?: string;
 }
 
@@ -323,7 +323,7 @@ if (shouldDeploy.toLowerCase() === "y" || shouldDeploy.toLowerCase() === "yes")
   console.log();
   
   try {
-    await $`stellar contract invoke --id ${contractId} --source deployer --network testnet -- initialize --admin ${adminAddress} --image_id ${imageId} --required_trials 7`;
+    await $`stellar contract invoke --id ${contractId} --source deployer --network testnet -- initialize --admin ${adminAddress} --image_id ${imageId} --required_score 7`;
     success("Contract initialized successfully");
   } catch (err) {
     error("Contract initialization failed");